@@ -14,7 +14,7 @@
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
-pub use self::asset::Asset;
+pub use self::asset::{Asset, Operation as AssetOperation};
 use ink_lang as ink;
 
 #[ink::contract]
@@ -25,8 +25,24 @@ mod asset {
         lazy::Lazy,
     };
 
+    use ink_storage::traits::{PackedLayout, SpreadLayout};
     use ink_prelude::string::String;
 
+    /// The operations of the token which can be paused by the guardian.
+    #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, scale::Encode, scale::Decode, SpreadLayout, PackedLayout)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink_storage::traits::StorageLayout)
+    )]
+    pub enum Operation {
+        /// `transfer` and `transfer_from`.
+        Transfer,
+        /// `mint`.
+        Mint,
+        /// `burn` and `burn_from`.
+        Burn,
+    }
+
     /// A simple ERC-20 contract.
     #[ink(storage)]
     pub struct Asset {
@@ -45,6 +61,10 @@ mod asset {
         operator: AccountId,
         /// from another account.
         allowances: StorageHashMap<(AccountId, AccountId), Balance>,
+        /// Account which can pause the operations in an emergency.
+        guardian: AccountId,
+        /// The operations which are paused.
+        paused: StorageHashMap<Operation, bool>,
     }
 
     /// Event emitted when a token transfer occurs.
@@ -70,6 +90,31 @@ mod asset {
         value: Balance,
     }
 
+    /// Event emitted when the guardian or the operator pause an operation.
+    #[ink(event)]
+    pub struct Paused {
+        #[ink(topic)]
+        operation: Operation,
+        #[ink(topic)]
+        by: AccountId,
+    }
+
+    /// Event emitted when the operator unpause an operation.
+    #[ink(event)]
+    pub struct Unpaused {
+        #[ink(topic)]
+        operation: Operation,
+        #[ink(topic)]
+        by: AccountId,
+    }
+
+    /// Event emitted when the operator switch the guardian.
+    #[ink(event)]
+    pub struct GuardianChanged {
+        #[ink(topic)]
+        guardian: AccountId,
+    }
+
     /// The ERC-20 error types.
     #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
     #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
@@ -103,6 +148,8 @@ mod asset {
                 balances,
                 allowances: StorageHashMap::new(),
                 operator: caller,
+                guardian: caller,
+                paused: StorageHashMap::new(),
             };
             Self::env().emit_event(Transfer {
                 from: None,
@@ -117,6 +164,10 @@ mod asset {
             assert!(self.operator == sender, "Asset: caller is not the operator");
         }
 
+        fn _when_not_paused(&self, operation: Operation) {
+            assert!(!self.is_paused(operation), "Asset: operation is paused");
+        }
+
         #[ink(message)]
         pub fn operator(&self) -> AccountId {
             return self.operator;
@@ -128,6 +179,51 @@ mod asset {
             self.operator = new_operator;
         }
 
+        /// Get the guardian who can pause the operations.
+        #[ink(message)]
+        pub fn guardian(&self) -> AccountId {
+            return self.guardian;
+        }
+
+        /// Switch the guardian. Only the operator can do it.
+        #[ink(message)]
+        pub fn set_guardian(&mut self, new_guardian: AccountId) {
+            self._only_operator();
+            self.guardian = new_guardian;
+            self.env().emit_event(GuardianChanged {
+                guardian: new_guardian,
+            });
+        }
+
+        /// Whether the operation is paused.
+        #[ink(message)]
+        pub fn is_paused(&self, operation: Operation) -> bool {
+            return self.paused.get(&operation).copied().unwrap_or(false);
+        }
+
+        /// Pause the operation. Called by the guardian or the operator.
+        #[ink(message)]
+        pub fn pause(&mut self, operation: Operation) {
+            let sender = Self::env().caller();
+            assert!(sender == self.guardian || sender == self.operator, "Asset: caller is not the guardian");
+            self.paused.insert(operation, true);
+            self.env().emit_event(Paused {
+                operation,
+                by: sender,
+            });
+        }
+
+        /// Unpause the operation. Only the operator can do it.
+        #[ink(message)]
+        pub fn unpause(&mut self, operation: Operation) {
+            self._only_operator();
+            self.paused.take(&operation);
+            self.env().emit_event(Unpaused {
+                operation,
+                by: Self::env().caller(),
+            });
+        }
+
         /// Returns the name.
         #[ink(message)]
         pub fn name(&self) -> Option<String> {
@@ -246,6 +342,7 @@ mod asset {
             to: AccountId,
             value: Balance,
         ) -> Result<()> {
+            self._when_not_paused(Operation::Transfer);
             let from_balance = self.balance_of(from);
             if from_balance < value {
                 return Err(Error::InsufficientBalance)
@@ -274,6 +371,7 @@ mod asset {
             value: Balance,
         ) -> Result<()> {
             self._only_operator();
+            self._when_not_paused(Operation::Mint);
             let balance_before = self.balance_of(to);
             let ar = balance_before.checked_add(value).expect("failed at mint the `asset` contract");
             self.balances.insert(to, ar);
@@ -305,6 +403,7 @@ mod asset {
             &mut self,
             value: Balance,
         ) -> Result<()> {
+            self._when_not_paused(Operation::Burn);
             let caller = self.env().caller();
             let from_balance = self.balance_of(caller);
             if from_balance < value {
@@ -340,6 +439,7 @@ mod asset {
             value: Balance,
         ) -> Result<()> {
             self._only_operator();
+            self._when_not_paused(Operation::Burn);
             let caller = self.env().caller();
             let allowance = self.allowance(from, caller);
             if allowance < value {
@@ -509,6 +609,34 @@ mod asset {
             assert_eq!(erc20.balance_of(accounts.eve), 10);
             assert_eq!(erc20.balance_of(accounts.bob), 0);
         }
+
+        #[ink::test]
+        fn pause_and_unpause_works() {
+            let mut erc20 = Asset::new(100, Some("test".to_string()), Some("test".to_string()), Some(10));
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            erc20.pause(Operation::Transfer);
+            assert!(erc20.is_paused(Operation::Transfer));
+            assert!(!erc20.is_paused(Operation::Mint));
+
+            erc20.unpause(Operation::Transfer);
+            assert!(!erc20.is_paused(Operation::Transfer));
+            assert_eq!(erc20.transfer(accounts.bob, 10), Ok(()));
+        }
+
+        #[ink::test]
+        #[should_panic(expected = "Asset: operation is paused")]
+        fn paused_transfer_should_fail() {
+            let mut erc20 = Asset::new(100, Some("test".to_string()), Some("test".to_string()), Some(10));
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+
+            erc20.pause(Operation::Transfer);
+            let _ = erc20.transfer(accounts.bob, 10);
+        }
     }
 
     /// For calculating the event topic hash.
@@ -14,7 +14,7 @@
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
-pub use self::boardroom::Boardroom;
+pub use self::boardroom::{Boardroom, Operation as BoardroomOperation};
 use ink_lang as ink;
 
 #[ink::contract]
@@ -30,11 +30,37 @@ mod boardroom {
         traits::{PackedLayout, SpreadLayout},
     };
     
-    use ink_env::call::FromAccountId;
-    use core::convert::TryInto;
+    use ink_env::call::{FromAccountId, ToAccountId};
 
     use util::Util;
     use asset::Asset;
+    use tokenstub::TokenStub;
+    use guard::Guard;
+
+    /// The max epochs the ALS can be locked after the stake.
+    const MAX_LOCKUP_EPOCHS: u32 = 56;
+
+    /// The default time(ms) the allocated reward is streamed over.
+    const DEFAULT_REWARD_DURATION: u64 = 24 * 60 * 60 * 1000;
+
+    /// The max number of the records returned by a query.
+    const MAX_PAGE_SIZE: u32 = 100;
+
+    /// The max number of the extra reward tokens.
+    const MAX_REWARD_TOKENS: u32 = 8;
+
+    /// The time(ms) of a year.
+    const YEAR: u128 = 365 * 24 * 60 * 60 * 1000;
+
+    /// The denominator of the basis point.
+    const BASIS_POINT: u128 = 10000;
+
+    /// The lock ends are rounded down to a step of `max_duration / LOCK_STEPS`, the boosts ending at the same step
+    /// are stopped together.
+    const LOCK_STEPS: u64 = 52;
+
+    /// The scale of the reward per share, it is much higher than the decimal of the tokens to keep the rounding small.
+    const REWARD_PRECISION: u128 = 1_000_000_000_000_000_000;
 
     /// Event emitted when a stake occurs that user Stake the ALS.
     #[ink(event)]
@@ -43,6 +69,7 @@ mod boardroom {
         user: Option<AccountId>,
         #[ink(topic)]
         amount: u128,
+        payer: AccountId,
     }
 
     /// Event emitted when an withdraw occurs that user withdraw the ALS which is staked before.
@@ -61,6 +88,7 @@ mod boardroom {
         user: Option<AccountId>,
         #[ink(topic)]
         reward: u128,
+        recipient: AccountId,
     }
 
     /// Event emitted when an allocate_seigniorage occurs that treasury allocate the reward.
@@ -72,8 +100,136 @@ mod boardroom {
         reward: u128,
     }
 
+    /// Event emitted when the operator add an extra reward token.
+    #[ink(event)]
+    pub struct RewardTokenAdded {
+        #[ink(topic)]
+        token: AccountId,
+        #[ink(topic)]
+        distributor: AccountId,
+    }
+
+    /// Event emitted when an allocate_reward occurs that the distributor allocate the extra reward token.
+    #[ink(event)]
+    pub struct RewardTokenAllocated {
+        #[ink(topic)]
+        token: AccountId,
+        #[ink(topic)]
+        user: Option<AccountId>,
+        reward: u128,
+    }
+
+    /// Event emitted when a claim_reward occurs that user claim the extra reward token.
+    #[ink(event)]
+    pub struct RewardTokenPaid {
+        #[ink(topic)]
+        token: AccountId,
+        #[ink(topic)]
+        user: Option<AccountId>,
+        reward: u128,
+        recipient: AccountId,
+    }
+
+    /// Event emitted when user lock the staked ALS for the boosted weight.
+    #[ink(event)]
+    pub struct Locked {
+        #[ink(topic)]
+        user: Option<AccountId>,
+        #[ink(topic)]
+        unlock_time: u64,
+        weight: u128,
+    }
+
+    /// Event emitted when the director's weight is refreshed.
+    #[ink(event)]
+    pub struct WeightUpdated {
+        #[ink(topic)]
+        user: Option<AccountId>,
+        weight: u128,
+    }
+
+    /// Event emitted when the guardian or the operator pause an operation.
+    #[ink(event)]
+    pub struct Paused {
+        #[ink(topic)]
+        operation: Operation,
+        #[ink(topic)]
+        by: AccountId,
+    }
+
+    /// Event emitted when the operator unpause an operation.
+    #[ink(event)]
+    pub struct Unpaused {
+        #[ink(topic)]
+        operation: Operation,
+        #[ink(topic)]
+        by: AccountId,
+    }
+
+    /// Event emitted when the operator recover the token sent to this contract by mistake.
+    #[ink(event)]
+    pub struct UnsupportedRecovered {
+        #[ink(topic)]
+        token: AccountId,
+        #[ink(topic)]
+        to: AccountId,
+        amount: u128,
+    }
+
+    /// Event emitted when the operator sweep the rounding dust of the reward.
+    #[ink(event)]
+    pub struct DustSwept {
+        #[ink(topic)]
+        token: AccountId,
+        #[ink(topic)]
+        to: AccountId,
+        amount: u128,
+    }
+
+    /// Event emitted when the director withdraw the ALS by emergency and forfeit the reward.
+    #[ink(event)]
+    pub struct EmergencyWithdrawn {
+        #[ink(topic)]
+        user: Option<AccountId>,
+        #[ink(topic)]
+        amount: u128,
+        forfeited: u128,
+    }
+
+    /// Event emitted when the director approves or revokes an operator.
+    #[ink(event)]
+    pub struct OperatorApproved {
+        #[ink(topic)]
+        owner: AccountId,
+        #[ink(topic)]
+        operator: AccountId,
+        approved: bool,
+    }
+
+    /// Event emitted when the operator switch the guardian.
+    #[ink(event)]
+    pub struct GuardianChanged {
+        #[ink(topic)]
+        guardian: AccountId,
+    }
+
+    /// The operations of the boardroom which can be paused by the guardian.
+    #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, scale::Encode, scale::Decode, SpreadLayout, PackedLayout)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink_storage::traits::StorageLayout)
+    )]
+    pub enum Operation {
+        Stake,
+        Withdraw,
+        ClaimReward,
+        AllocateSeigniorage,
+        AllocateReward,
+    }
+
     /// BoardSeat record the reward should paid to user.
-    /// When treasury allocate the reward, boardroom generate the new BoardSeat for user who staked the ALS. 
+    /// The reward is settled to `reward_earned` with the reward per ALS since `reward_per_share_paid`
+    /// when user stake, withdraw or claim.
     #[derive(Debug, PartialEq, Eq, Clone, scale::Encode, scale::Decode, SpreadLayout, PackedLayout)]
     #[cfg_attr(
         feature = "std",
@@ -82,9 +238,10 @@ mod boardroom {
     pub struct BoardSeat {
         pub last_snapshot_index: u128,
         pub reward_earned: u128,
+        pub reward_per_share_paid: u128,
     }
 
-    /// BoardSnapshot record the reward per ALS.
+    /// BoardSnapshot record the reward allocated and the reward per ALS at that time, it is kept for reporting.
     /// When treasury allocate the reward, boardroom generate the new BoardSnapshot.
     #[derive(Debug, PartialEq, Eq, Clone, scale::Encode, scale::Decode, SpreadLayout, PackedLayout)]
     #[cfg_attr(
@@ -97,6 +254,73 @@ mod boardroom {
         pub reward_per_share: u128,
     }
 
+    /// Lockup record the time(ms) of the director's last stake and last claim, the withdraw and the claim
+    /// are locked for some epochs after them.
+    #[derive(Debug, PartialEq, Eq, Clone, Default, scale::Encode, scale::Decode, SpreadLayout, PackedLayout)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink_storage::traits::StorageLayout)
+    )]
+    pub struct Lockup {
+        pub stake_time: u64,
+        pub claim_time: u64,
+    }
+
+    /// RewardState record the stream of an extra reward token, like the ALC reward.
+    /// `distributor` can allocate the token besides the operator.
+    #[derive(Debug, PartialEq, Eq, Clone, scale::Encode, scale::Decode, SpreadLayout, PackedLayout)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink_storage::traits::StorageLayout)
+    )]
+    pub struct RewardState {
+        pub distributor: AccountId,
+        pub reward_rate: u128,
+        pub period_finish: u64,
+        pub last_update_time: u64,
+        pub reward_per_share_stored: u128,
+        pub snapshot_count: u32,
+    }
+
+    /// TokenSeat record the extra reward token should paid to user.
+    #[derive(Debug, PartialEq, Eq, Clone, Default, scale::Encode, scale::Decode, SpreadLayout, PackedLayout)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink_storage::traits::StorageLayout)
+    )]
+    pub struct TokenSeat {
+        pub reward_earned: u128,
+        pub reward_per_share_paid: u128,
+    }
+
+    /// LockMode record the config of the vote-escrowed ALS. When it is enabled, the director can lock the staked ALS
+    /// for up to `max_duration`(ms), and the weight is boosted by up to `max_boost` basis point of the balance.
+    /// The boost is set by the lock time when the director locks, and stops when the lock ends.
+    #[derive(Debug, PartialEq, Eq, Clone, Default, scale::Encode, scale::Decode, SpreadLayout, PackedLayout)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink_storage::traits::StorageLayout)
+    )]
+    pub struct LockMode {
+        pub enabled: bool,
+        pub max_duration: u64,
+        pub max_boost: u128,
+    }
+
+    /// VoteLock record the director's lock: the end(ms), the boost rate in basis point of the balance,
+    /// the boost counted in the total weight and the generation of the lock mode the lock is made in.
+    #[derive(Debug, PartialEq, Eq, Clone, Default, scale::Encode, scale::Decode, SpreadLayout, PackedLayout)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink_storage::traits::StorageLayout)
+    )]
+    pub struct VoteLock {
+        pub end: u64,
+        pub boost_rate: u128,
+        pub boost: u128,
+        pub generation: u32,
+    }
+
     #[ink(storage)]
     pub struct Boardroom {
         util: Lazy<Util>,
@@ -108,7 +332,44 @@ mod boardroom {
         board_history: StorageVec<BoardSnapshot>,
 
         operator: AccountId,
-        status: HashMap<(u32, AccountId), bool>,
+        guardian: AccountId,
+        paused: HashMap<Operation, bool>,
+        guard: Guard,
+
+        withdraw_lockup_epochs: u32,
+        reward_lockup_epochs: u32,
+        lockup_period: u64,
+        lockups: HashMap<AccountId, Lockup>,
+
+        reward_duration: u64,
+        reward_rate: u128,
+        period_finish: u64,
+        last_update_time: u64,
+        reward_per_share_stored: u128,
+
+        reward_tokens: StorageVec<AccountId>,
+        reward_states: HashMap<AccountId, RewardState>,
+        token_seats: HashMap<(AccountId, AccountId), TokenSeat>,
+        token_history: HashMap<(AccountId, u32), BoardSnapshot>,
+
+        lock_mode: LockMode,
+        locks: HashMap<AccountId, VoteLock>,
+        weights: HashMap<AccountId, u128>,
+        weight_total: u128,
+        /// The generation of the lock mode, it advances when the lock mode is disabled.
+        lock_generation: u32,
+        lock_disabled_at: HashMap<u32, u64>,
+        /// The last lock step whose boosts are stopped, and the latest lock end.
+        lock_checkpoint: u64,
+        last_lock_end: u64,
+        /// The boosts ending at a lock step, and the reward per weight of each token when the boosts stop.
+        boost_expiring: HashMap<(u32, u64), u128>,
+        boost_rps: HashMap<(AccountId, u64), u128>,
+
+        approvals: HashMap<(AccountId, AccountId), bool>,
+        forfeited: HashMap<AccountId, u128>,
+        carried: HashMap<AccountId, u128>,
+        dust: HashMap<AccountId, u128>,
     }
 
     impl Boardroom {
@@ -138,21 +399,52 @@ mod boardroom {
                 directors: HashMap::new(),
                 board_history: history,
                 operator: sender,
-                status: HashMap::new(),
+                guardian: sender,
+                paused: HashMap::new(),
+                guard: Guard::new(),
+
+                withdraw_lockup_epochs: 0,
+                reward_lockup_epochs: 0,
+                lockup_period: 0,
+                lockups: HashMap::new(),
+
+                reward_duration: DEFAULT_REWARD_DURATION,
+                reward_rate: 0,
+                period_finish: 0,
+                last_update_time: Self::env().block_timestamp(),
+                reward_per_share_stored: 0,
+
+                reward_tokens: StorageVec::new(),
+                reward_states: HashMap::new(),
+                token_seats: HashMap::new(),
+                token_history: HashMap::new(),
+
+                lock_mode: Default::default(),
+                locks: HashMap::new(),
+                weights: HashMap::new(),
+                weight_total: 0,
+                lock_generation: 0,
+                lock_disabled_at: HashMap::new(),
+                lock_checkpoint: 0,
+                last_lock_end: 0,
+                boost_expiring: HashMap::new(),
+                boost_rps: HashMap::new(),
+
+                approvals: HashMap::new(),
+                forfeited: HashMap::new(),
+                carried: HashMap::new(),
+                dust: HashMap::new(),
             }
         }
 
-        fn _check_same_sender_rented(&self) {
+        fn _guard_enter(&mut self) {
             let block_num:u32 = Self::env().block_number();
             let sender = Self::env().caller();
-            let rented:bool = self.status.get(&(block_num, sender)).copied().unwrap_or(false);
-            assert!(!rented, "Boardroom: : _check_same_sender_rented err");
+            self.guard.enter(sender, block_num);
         }
 
-        fn _update_sender_rented_status(&mut self) {
-            let block_num:u32 = Self::env().block_number();
-            let sender = Self::env().caller();
-            self.status.insert((block_num, sender), true);
+        fn _guard_exit(&mut self) {
+            self.guard.exit();
         }
 
         fn _director_exists(&self) {
@@ -161,46 +453,236 @@ mod boardroom {
             assert!(b > 0, "Boardroom: : The director does not exist");
         }
 
+        /// Settle the streamed reward per ALS until now, the boosts of the ended locks are stopped first.
+        fn _update_reward_per_share(&mut self) {
+            self._checkpoint_locks();
+            let (rps, dust) = self._stream_reward(self.reward_per_share_stored, self.reward_rate, self.last_update_time, self.period_finish);
+            let cash: AccountId = self.cash.to_account_id();
+            self._add_dust(cash, dust);
+            self.reward_per_share_stored = rps;
+            self.last_update_time = self.last_time_reward_applicable();
+        }
+
         fn _update_reward(&mut self, director:AccountId) {
             assert_ne!(director, AccountId::from([0; 32]));
-            let earned = self._earned(director);
+            self._update_reward_per_share();
+            let rps = self.reward_per_share_stored;
+            let seat = self._get_director_board_seat(director).unwrap();
+            let cash: AccountId = self.cash.to_account_id();
+            let (pending, dust) = self._director_pending(cash, director, rps, seat.reward_per_share_paid);
+            self._add_dust(cash, dust);
+            let earned: u128 = pending.checked_add(seat.reward_earned).expect("failed at _update_reward the `boardroom` contract");
             let index = self.latest_snapshot_index();
-            self._update_seat(director, earned, index);
+            self._update_seat(director, earned, index, rps);
+
+            let tokens: Vec<AccountId> = self.reward_tokens.iter().copied().collect();
+            for token in tokens {
+                self._update_token_reward(token, director);
+            }
+
+            // The boost is stopped, the director earns with the staked ALS from now.
+            let mut lock = self._lock_of(director);
+            if lock.boost > 0 && !self._boost_counted(&lock) {
+                lock.boost = 0;
+                self.locks.insert(director, lock);
+                self.weights.insert(director, self.balance_of(director));
+            }
         }
 
-        fn _earned(&self, director:AccountId) -> u128 {
-            let latest_rps: u128 = self._get_latest_snapshot().reward_per_share;
-            let stored_rps: u128 = self._get_last_snapshot_of(director).reward_per_share;
+        fn _reward_state(&self, token: AccountId) -> RewardState {
+            return self.reward_states.get(&token).expect("Boardroom: reward token does not exist").clone();
+        }
 
-            let latest_rps_sub: u128 = latest_rps.checked_sub(stored_rps).expect("failed at _earned the `boardroom` contract");
+        fn _token_seat(&self, token: AccountId, director: AccountId) -> TokenSeat {
+            return self.token_seats.get(&(token, director)).cloned().unwrap_or_default();
+        }
 
-            let balance: u128 = self.balance_of(director);
-            let balance_mul: u128 = balance.checked_mul(latest_rps_sub).expect("failed at _earned the `boardroom` contract");
+        fn _last_time_applicable(&self, period_finish: u64) -> u64 {
+            let now: u64 = Self::env().block_timestamp();
+            return self._time_applicable(now, period_finish);
+        }
 
-            let one_unit: u128 = self.util.get_one_unit_with_decimal();
-            let balance_mul_div: u128 = balance_mul.checked_div(one_unit).expect("failed at _earned the `boardroom` contract");
+        fn _time_applicable(&self, until: u64, period_finish: u64) -> u64 {
+            if until < period_finish {
+                return until;
+            }
+            return period_finish;
+        }
+
+        /// Get the reward per weight of the reward stream, including the reward streamed since `last_update_time`.
+        /// The weight is the staked ALS boosted by the lock.
+        fn _stream_reward_per_share(&self, stored: u128, rate: u128, last_update_time: u64, period_finish: u64) -> u128 {
+            let (rps, _) = self._stream_reward(stored, rate, last_update_time, period_finish);
+            return rps;
+        }
+
+        /// Get the reward per weight of the reward stream and the dust lost by the rounding(scaled by `REWARD_PRECISION`).
+        /// The reward streamed while nothing is staked is dust too.
+        fn _stream_reward(&self, stored: u128, rate: u128, last_update_time: u64, period_finish: u64) -> (u128, u128) {
+            let now: u64 = Self::env().block_timestamp();
+            return self._stream_reward_until(stored, rate, last_update_time, period_finish, now);
+        }
+
+        /// Get the reward per weight of the reward stream until `until` and the dust, like `_stream_reward`.
+        fn _stream_reward_until(&self, stored: u128, rate: u128, last_update_time: u64, period_finish: u64, until: u64) -> (u128, u128) {
+            let elapsed: u64 = self._time_applicable(until, period_finish).saturating_sub(last_update_time);
+            let streamed: u128 = rate.checked_mul(elapsed as u128).expect("failed at _stream_reward the `boardroom` contract");
+            let streamed_mul: u128 = streamed.checked_mul(REWARD_PRECISION).expect("failed at _stream_reward the `boardroom` contract");
+            let total: u128 = self.weight_total;
+            if total == 0 {
+                return (stored, streamed_mul);
+            }
+
+            let streamed_div: u128 = streamed_mul.checked_div(total).expect("failed at _stream_reward the `boardroom` contract");
+            let rps: u128 = stored.checked_add(streamed_div).expect("failed at _stream_reward the `boardroom` contract");
+            return (rps, streamed_mul % total);
+        }
+
+        /// Get the reward of `weight` between `rps_paid` and `rps`, and the dust lost by the rounding(scaled by `REWARD_PRECISION`).
+        fn _pending(&self, weight: u128, rps: u128, rps_paid: u128) -> (u128, u128) {
+            let rps_sub: u128 = rps.checked_sub(rps_paid).expect("failed at _pending the `boardroom` contract");
+            let weight_mul: u128 = weight.checked_mul(rps_sub).expect("failed at _pending the `boardroom` contract");
+            return (weight_mul / REWARD_PRECISION, weight_mul % REWARD_PRECISION);
+        }
+
+        fn _add_dust(&mut self, token: AccountId, dust: u128) {
+            if dust == 0 {
+                return;
+            }
+            let total: u128 = self.dust.get(&token).copied().unwrap_or(0).saturating_add(dust);
+            self.dust.insert(token, total);
+        }
+
+        fn _token_reward_per_share(&self, state: &RewardState) -> u128 {
+            return self._stream_reward_per_share(state.reward_per_share_stored, state.reward_rate, state.last_update_time, state.period_finish);
+        }
+
+        /// Settle the streamed reward per ALS of the token until now.
+        fn _settle_token_stream(&mut self, token: AccountId, state: &mut RewardState) {
+            let (rps, dust) = self._stream_reward(state.reward_per_share_stored, state.reward_rate, state.last_update_time, state.period_finish);
+            self._add_dust(token, dust);
+            state.reward_per_share_stored = rps;
+            state.last_update_time = self._last_time_applicable(state.period_finish);
+        }
 
+        fn _token_earned(&self, token: AccountId, director: AccountId) -> u128 {
+            let state = self._reward_state(token);
+            let seat = self._token_seat(token, director);
+            let (pending, _) = self._director_pending(token, director, self._token_reward_per_share(&state), seat.reward_per_share_paid);
+            return pending.checked_add(seat.reward_earned).expect("failed at _token_earned the `boardroom` contract");
+        }
+
+        fn _update_token_reward(&mut self, token: AccountId, director: AccountId) {
+            let mut state = self._reward_state(token);
+            self._settle_token_stream(token, &mut state);
+            let rps: u128 = state.reward_per_share_stored;
+            self.reward_states.insert(token, state);
+
+            let seat = self._token_seat(token, director);
+            let (pending, dust) = self._director_pending(token, director, rps, seat.reward_per_share_paid);
+            self._add_dust(token, dust);
+            let earned: u128 = pending.checked_add(seat.reward_earned).expect("failed at _update_token_reward the `boardroom` contract");
+            self.token_seats.insert((token, director), TokenSeat {
+                reward_earned: earned,
+                reward_per_share_paid: rps,
+            });
+        }
+
+        fn _earned(&self, director:AccountId) -> u128 {
             let seat = self._get_director_board_seat(director).unwrap();
-            let earned: u128 = seat.reward_earned;
-            let ret: u128 = balance_mul_div.checked_add(earned).expect("failed at _earned the `boardroom` contract");
+            let cash: AccountId = self.cash.to_account_id();
+            let (pending, _) = self._director_pending(cash, director, self.reward_per_share(), seat.reward_per_share_paid);
+            let ret: u128 = pending.checked_add(seat.reward_earned).expect("failed at _earned the `boardroom` contract");
             return ret;
         }
 
-        /// Get the snapshots for the rewards.
+        /// Get the snapshots for the rewards from `offset`, no more than `limit`(at most 100).
         #[ink(message)]
-        pub fn get_snapshots(&self) -> Vec<BoardSnapshot> {
+        pub fn get_snapshots(&self, offset: u32, limit: u32) -> Vec<BoardSnapshot> {
+            let limit: u32 = if limit > MAX_PAGE_SIZE { MAX_PAGE_SIZE } else { limit };
             let mut records:Vec<BoardSnapshot> = Vec::new();
-            for history in self.board_history.iter() {
-                let r = BoardSnapshot {
-                    time: history.time,
-                    reward_received: history.reward_received,
-                    reward_per_share: history.reward_per_share,
-                };
-                records.push(r);
+            for history in self.board_history.iter().skip(offset as usize).take(limit as usize) {
+                records.push(history.clone());
+            }
+            return records;
+        }
+
+        /// Get the snapshots whose time(ms) is between `from_time` and `to_time`, no more than 100 from the earliest.
+        #[ink(message)]
+        pub fn get_snapshots_between(&self, from_time: u64, to_time: u64) -> Vec<BoardSnapshot> {
+            let mut records:Vec<BoardSnapshot> = Vec::new();
+            if from_time > to_time {
+                return records;
+            }
+
+            // the snapshots are sorted by time, find the first one not earlier than `from_time`.
+            let mut low: u32 = 0;
+            let mut high: u32 = self.board_history.len();
+            while low < high {
+                let mid: u32 = low + (high - low) / 2;
+                if self.board_history.get(mid).unwrap().time < from_time {
+                    low = mid + 1;
+                } else {
+                    high = mid;
+                }
+            }
+
+            for history in self.board_history.iter().skip(low as usize).take(MAX_PAGE_SIZE as usize) {
+                if history.time > to_time {
+                    break;
+                }
+                records.push(history.clone());
             }
             return records;
         }
 
+        /// Get the count of the snapshots.
+        #[ink(message)]
+        pub fn snapshot_count(&self) -> u32 {
+            return self.board_history.len();
+        }
+
+        /// Get the APR in basis point from the reward of the last `count` allocations, with the ALC and the ALS price.
+        /// The reward is annualized over the time since the first of them until the stream finishes.
+        #[ink(message)]
+        pub fn get_apr(&self, count: u32, cash_price: u128, share_price: u128) -> u128 {
+            // the genesis snapshot is not an allocation.
+            let allocations: u32 = self.board_history.len() - 1;
+            let count: u32 = if count > allocations { allocations } else { count };
+            let total: u128 = self.total_supply();
+            if count == 0 || total == 0 || share_price == 0 {
+                return 0;
+            }
+
+            let first: u32 = allocations + 1 - count;
+            let mut rewards: u128 = 0;
+            for history in self.board_history.iter().skip(first as usize) {
+                rewards = rewards.checked_add(history.reward_received).expect("failed at get_apr the `boardroom` contract");
+            }
+
+            let now: u64 = Self::env().block_timestamp();
+            let end: u64 = if now > self.period_finish { now } else { self.period_finish };
+            let start: u64 = self.board_history.get(first).unwrap().time;
+            let span: u128 = end.checked_sub(start).expect("failed at get_apr the `boardroom` contract") as u128;
+            if span == 0 {
+                return 0;
+            }
+
+            let one_unit: u128 = self.util.get_one_unit_with_decimal();
+            let reward_value: u128 = rewards.checked_mul(cash_price).expect("failed at get_apr the `boardroom` contract");
+            let reward_value: u128 = reward_value.checked_div(one_unit).expect("failed at get_apr the `boardroom` contract");
+            let stake_value: u128 = total.checked_mul(share_price).expect("failed at get_apr the `boardroom` contract");
+            let stake_value: u128 = stake_value.checked_div(one_unit).expect("failed at get_apr the `boardroom` contract");
+            if stake_value == 0 {
+                return 0;
+            }
+
+            let yearly: u128 = reward_value.checked_mul(BASIS_POINT).expect("failed at get_apr the `boardroom` contract");
+            let yearly: u128 = yearly.checked_mul(YEAR).expect("failed at get_apr the `boardroom` contract");
+            let yearly: u128 = yearly.checked_div(span).expect("failed at get_apr the `boardroom` contract");
+            return yearly.checked_div(stake_value).expect("failed at get_apr the `boardroom` contract");
+        }
+
         /// Get the everyone's rewards detail.
         #[ink(message)]
         pub fn get_seats(&self) -> Vec<BoardSeat> {
@@ -209,26 +691,50 @@ mod boardroom {
                 let r = BoardSeat {
                     last_snapshot_index: value.last_snapshot_index,
                     reward_earned: value.reward_earned,
+                    reward_per_share_paid: value.reward_per_share_paid,
                 };
                 records.push(r);
             }
             return records;
         }
 
-        fn _get_latest_snapshot(&self) -> BoardSnapshot {
-            let index = self.latest_snapshot_index();
-            return self.board_history[index.try_into().unwrap()].clone();
+        /// Get the ALC reward the director can claim, including the reward streamed until now.
+        #[ink(message)]
+        pub fn earned(&self, account: AccountId) -> u128 {
+            return self._earned(account);
         }
 
-        fn _get_last_snapshot_of(&self, director:AccountId) -> BoardSnapshot {
-            let index = self.get_last_snapshot_index_of(director);
-            return self.board_history[index.try_into().unwrap()].clone();
+        /// Get the director's seat, None if the director never staked.
+        #[ink(message)]
+        pub fn get_seat(&self, account: AccountId) -> Option<BoardSeat> {
+            return self.directors.get(&account).cloned();
+        }
+
+        /// Get the directors from `offset`, no more than `limit`(at most 100),
+        /// with their staked ALS, seats and the reward they can claim.
+        #[ink(message)]
+        pub fn get_directors(&self, offset: u32, limit: u32) -> Vec<(AccountId, u128, BoardSeat, u128)> {
+            let limit: u32 = if limit > MAX_PAGE_SIZE { MAX_PAGE_SIZE } else { limit };
+            let mut records: Vec<(AccountId, u128, BoardSeat, u128)> = Vec::new();
+            for (account, seat) in self.directors.iter().skip(offset as usize).take(limit as usize) {
+                let balance: u128 = self.balance_of(*account);
+                let earned: u128 = self._earned(*account);
+                records.push((*account, balance, seat.clone(), earned));
+            }
+            return records;
+        }
+
+        /// Get the count of the directors.
+        #[ink(message)]
+        pub fn director_count(&self) -> u32 {
+            return self.directors.len();
         }
 
         fn _build_empty_board_seat(&self) -> BoardSeat {
             BoardSeat {
                 last_snapshot_index: 0,
                 reward_earned: 0,
+                reward_per_share_paid: 0,
             }
         }
 
@@ -242,12 +748,13 @@ mod boardroom {
             return Some(self.directors.get(&account).unwrap().clone());
         }
 
-        fn _update_seat(&mut self, account: AccountId, earned: u128, snap_shot_index: u128) {
+        fn _update_seat(&mut self, account: AccountId, earned: u128, snap_shot_index: u128, reward_per_share_paid: u128) {
             let exist = self.directors.contains_key(&account);
             if !exist {
                 let mut r = self._build_empty_board_seat();
                 r.reward_earned = earned;
                 r.last_snapshot_index = snap_shot_index;
+                r.reward_per_share_paid = reward_per_share_paid;
                 self.directors.insert(account, r);
                 return;
             }
@@ -255,20 +762,22 @@ mod boardroom {
             if let Some(seat) = self.directors.get_mut(&account) {
                 seat.reward_earned = earned;
                 seat.last_snapshot_index = snap_shot_index;
+                seat.reward_per_share_paid = reward_per_share_paid;
             }
         }
 
-        fn _stake(&mut self, amount: u128) {
+        /// Take the ALS from `payer` and stake it for `beneficiary`.
+        fn _stake(&mut self, payer: AccountId, beneficiary: AccountId, amount: u128) {
             let total:u128 = self.stake_total;
             self.stake_total = total.checked_add(amount).expect("failed at _stake the `boardroom` contract");
 
-            let sender = Self::env().caller();
-            let balance = self.balance_of(sender);
+            let balance = self.balance_of(beneficiary);
             let value = balance.checked_add(amount).expect("failed at _stake the `boardroom` contract");
-            self.balances.insert(sender, value);
+            self.balances.insert(beneficiary, value);
+            self._update_weight(beneficiary);
 
             let this = self.env().account_id();
-            let ret:bool = self.share.transfer_from(sender, this, amount).is_ok();
+            let ret:bool = self.share.transfer_from(payer, this, amount).is_ok();
             assert!(ret, "Boardroom: _withdraw share.transfer err");
         }
 
@@ -283,16 +792,224 @@ mod boardroom {
             let balance = self.balance_of(sender);
             let value = balance.checked_sub(amount).expect("failed at _withdraw the `boardroom` contract");
             self.balances.insert(sender, value);
+            self._update_weight(sender);
 
             let ret:bool = self.share.transfer(sender, amount).is_ok();
             assert!(ret, "Boardroom: _withdraw share.transfer err");
         }
 
+        /// Get the weight of the account from the balance and the boost of the lock.
+        fn _compute_weight(&self, account: AccountId) -> u128 {
+            let balance: u128 = self.balance_of(account);
+            let lock = self._lock_of(account);
+            let now: u64 = Self::env().block_timestamp();
+            if !self.lock_mode.enabled || lock.generation != self.lock_generation || lock.end <= now {
+                return balance;
+            }
+
+            let boost: u128 = balance.checked_mul(lock.boost_rate).expect("failed at _compute_weight the `boardroom` contract");
+            let boost: u128 = boost.checked_div(BASIS_POINT).expect("failed at _compute_weight the `boardroom` contract");
+            return balance.checked_add(boost).expect("failed at _compute_weight the `boardroom` contract");
+        }
+
+        /// Refresh the weight of the account, the reward should be settled before it.
+        /// The boost is counted until the lock end.
+        fn _update_weight(&mut self, account: AccountId) -> u128 {
+            let old_weight: u128 = self.weight_of(account);
+            let new_weight: u128 = self._compute_weight(account);
+            let mut lock = self._lock_of(account);
+            self._take_expiring(&lock);
+            lock.boost = new_weight.checked_sub(self.balance_of(account)).expect("failed at _update_weight the `boardroom` contract");
+            if lock.boost > 0 {
+                let key = (lock.generation, lock.end);
+                let expiring: u128 = self.boost_expiring.get(&key).copied().unwrap_or(0);
+                self.boost_expiring.insert(key, expiring.checked_add(lock.boost).expect("failed at _update_weight the `boardroom` contract"));
+                if lock.end > self.last_lock_end {
+                    self.last_lock_end = lock.end;
+                }
+            }
+            if lock != VoteLock::default() {
+                self.locks.insert(account, lock);
+            }
+
+            let total: u128 = self.weight_total.checked_sub(old_weight).expect("failed at _update_weight the `boardroom` contract");
+            self.weight_total = total.checked_add(new_weight).expect("failed at _update_weight the `boardroom` contract");
+            self.weights.insert(account, new_weight);
+            return new_weight;
+        }
+
+        fn _lock_of(&self, account: AccountId) -> VoteLock {
+            return self.locks.get(&account).cloned().unwrap_or_default();
+        }
+
+        fn _lock_step(&self) -> u64 {
+            return self.lock_mode.max_duration / LOCK_STEPS;
+        }
+
+        /// Whether the boost of the lock is still counted in the total weight.
+        fn _boost_counted(&self, lock: &VoteLock) -> bool {
+            return lock.boost > 0 && lock.generation == self.lock_generation && lock.end > self.lock_checkpoint;
+        }
+
+        /// Get the time(ms) the boost of the lock is stopped: the lock end, or the time the lock mode is disabled.
+        fn _boost_stop_time(&self, lock: &VoteLock) -> u64 {
+            if lock.generation == self.lock_generation {
+                return lock.end;
+            }
+            let disabled_at: u64 = self.lock_disabled_at.get(&lock.generation).copied().unwrap_or(lock.end);
+            if disabled_at < lock.end {
+                return disabled_at;
+            }
+            return lock.end;
+        }
+
+        /// Remove the counted boost of the lock from its lock step.
+        fn _take_expiring(&mut self, lock: &VoteLock) {
+            if !self._boost_counted(lock) {
+                return;
+            }
+            let key = (lock.generation, lock.end);
+            let expiring: u128 = self.boost_expiring.get(&key).copied().unwrap_or(0);
+            self.boost_expiring.insert(key, expiring.saturating_sub(lock.boost));
+        }
+
+        /// Get the director's reward of the token between `rps_paid` and `rps`. If the boost is stopped since the last
+        /// update, the boosted weight earns until the stop and the staked ALS earns after it.
+        fn _director_pending(&self, token: AccountId, director: AccountId, rps: u128, rps_paid: u128) -> (u128, u128) {
+            let weight: u128 = self.weight_of(director);
+            let lock = self._lock_of(director);
+            if lock.boost == 0 || self._boost_counted(&lock) {
+                return self._pending(weight, rps, rps_paid);
+            }
+
+            // The token is added after the stop if its reward per weight is not recorded.
+            let stop_time: u64 = self._boost_stop_time(&lock);
+            let stop_rps: u128 = self.boost_rps.get(&(token, stop_time)).copied().unwrap_or(0);
+            let stop_rps: u128 = if stop_rps < rps_paid { rps_paid } else if stop_rps > rps { rps } else { stop_rps };
+            let (boosted, boosted_dust) = self._pending(weight, stop_rps, rps_paid);
+            let (staked, staked_dust) = self._pending(self.balance_of(director), rps, stop_rps);
+            let pending: u128 = boosted.checked_add(staked).expect("failed at _director_pending the `boardroom` contract");
+            let dust: u128 = boosted_dust.checked_add(staked_dust).expect("failed at _director_pending the `boardroom` contract");
+            return (pending, dust);
+        }
+
+        /// Settle the ALC and the extra reward streams until `until`, and record the reward per weight at the time
+        /// for the boosts stopped then.
+        fn _settle_streams_until(&mut self, until: u64) {
+            let cash: AccountId = self.cash.to_account_id();
+            let (rps, dust) = self._stream_reward_until(self.reward_per_share_stored, self.reward_rate, self.last_update_time, self.period_finish, until);
+            self._add_dust(cash, dust);
+            self.reward_per_share_stored = rps;
+            let applicable: u64 = self._time_applicable(until, self.period_finish);
+            if applicable > self.last_update_time {
+                self.last_update_time = applicable;
+            }
+            self.boost_rps.insert((cash, until), rps);
+
+            let tokens: Vec<AccountId> = self.reward_tokens.iter().copied().collect();
+            for token in tokens {
+                let mut state = self._reward_state(token);
+                let (rps, dust) = self._stream_reward_until(state.reward_per_share_stored, state.reward_rate, state.last_update_time, state.period_finish, until);
+                self._add_dust(token, dust);
+                state.reward_per_share_stored = rps;
+                let applicable: u64 = self._time_applicable(until, state.period_finish);
+                if applicable > state.last_update_time {
+                    state.last_update_time = applicable;
+                }
+                self.boost_rps.insert((token, until), rps);
+                self.reward_states.insert(token, state);
+            }
+        }
+
+        /// Stop the boosts of the ended locks step by step, the streams are settled at each step with the boosts.
+        /// It runs at most `LOCK_STEPS` steps after the last one as no lock ends later.
+        fn _checkpoint_locks(&mut self) {
+            if !self.lock_mode.enabled {
+                return;
+            }
+            let step: u64 = self._lock_step();
+            let now: u64 = Self::env().block_timestamp();
+            let mut time: u64 = self.lock_checkpoint;
+            loop {
+                let next: u64 = time.checked_add(step).expect("failed at _checkpoint_locks the `boardroom` contract");
+                if next > now {
+                    break;
+                }
+                if next > self.last_lock_end {
+                    time = now - now % step;
+                    break;
+                }
+                let expiring: u128 = self.boost_expiring.take(&(self.lock_generation, next)).unwrap_or(0);
+                if expiring > 0 {
+                    self._settle_streams_until(next);
+                    self.weight_total = self.weight_total.checked_sub(expiring).expect("failed at _checkpoint_locks the `boardroom` contract");
+                }
+                time = next;
+            }
+            self.lock_checkpoint = time;
+        }
+
+        /// Get the reward settled to the director's seat without any panic, 0 if it overflows.
+        fn _settled_reward(&self, weight: u128, stored_rps: u128, rps_paid: u128, earned: u128) -> u128 {
+            return stored_rps.checked_sub(rps_paid)
+                .and_then(|rps_sub| weight.checked_mul(rps_sub))
+                .and_then(|mul| mul.checked_div(REWARD_PRECISION))
+                .and_then(|pending| pending.checked_add(earned))
+                .unwrap_or(earned);
+        }
+
+        /// Add the forfeited reward of the token, it is allocated again with the next allocation.
+        fn _forfeit(&mut self, token: AccountId, amount: u128) {
+            if amount == 0 {
+                return;
+            }
+            let forfeited: u128 = self.forfeited_reward(token).saturating_add(amount);
+            self.forfeited.insert(token, forfeited);
+        }
+
+        fn _take_forfeited(&mut self, token: AccountId) -> u128 {
+            return self.forfeited.take(&token).unwrap_or(0);
+        }
+
+        /// Get the reward to allocate: the new amount, the forfeited reward and the remainder carried from the last
+        /// allocation, plus the reward not streamed yet.
+        fn _next_reward(&mut self, token: AccountId, amount: u128, rate: u128, period_finish: u64) -> u128 {
+            let carried: u128 = self.carried.take(&token).unwrap_or(0);
+            let mut reward: u128 = amount.checked_add(self._take_forfeited(token)).expect("failed at _next_reward the `boardroom` contract");
+            reward = reward.checked_add(carried).expect("failed at _next_reward the `boardroom` contract");
+            let now: u64 = Self::env().block_timestamp();
+            if now < period_finish {
+                let remaining: u64 = period_finish.checked_sub(now).expect("failed at _next_reward the `boardroom` contract");
+                let leftover: u128 = rate.checked_mul(remaining as u128).expect("failed at _next_reward the `boardroom` contract");
+                reward = reward.checked_add(leftover).expect("failed at _next_reward the `boardroom` contract");
+            }
+            return reward;
+        }
+
+        /// Get the reward rate over `reward_duration`, the remainder of the division is carried to the next allocation.
+        fn _reward_rate(&mut self, token: AccountId, reward: u128) -> u128 {
+            let duration: u128 = self.reward_duration as u128;
+            let rate: u128 = reward.checked_div(duration).expect("failed at _reward_rate the `boardroom` contract");
+            let remainder: u128 = reward % duration;
+            if remainder > 0 {
+                self.carried.insert(token, remainder);
+            }
+            return rate;
+        }
+
+        fn _lockup_of(&self, account: AccountId) -> Lockup {
+            return self.lockups.get(&account).cloned().unwrap_or_default();
+        }
+
         fn _only_operator(&self) {
             let sender = Self::env().caller();
             assert!(self.operator == sender, "Boardroom: caller is not the operator");
         }
 
+        fn _when_not_paused(&self, operation: Operation) {
+            assert!(!self.is_paused(operation), "Boardroom: operation is paused");
+        }
+
         /// Get the operator who can operate this contract.
         #[ink(message)]
         pub fn operator(&self) -> AccountId {
@@ -306,11 +1023,204 @@ mod boardroom {
             self.operator = new_operator;
         }
 
-        /// Get the reward(ALC) amount per ALS.
+        /// Get the last block the account staked or withdrew in.
+        #[ink(message)]
+        pub fn last_action_block(&self, account: AccountId) -> Option<u32> {
+            return self.guard.last_action(&account);
+        }
+
+        /// Transfer the token sent to this contract by mistake to `to`. ALC and ALS can't be recovered.
+        #[ink(message)]
+        pub fn recover_unsupported(&mut self, token: AccountId, amount: u128, to: AccountId) {
+            self._only_operator();
+            assert!(token != self.cash.to_account_id() && token != self.share.to_account_id(), "Boardroom: protocol token can't be recovered");
+            assert!(!self.reward_states.contains_key(&token), "Boardroom: reward token can't be recovered");
+            let mut unsupported: TokenStub = FromAccountId::from_account_id(token);
+            let ret: bool = unsupported.transfer(to, amount).is_ok();
+            assert!(ret, "Boardroom: recover_unsupported transfer err");
+
+            self.env().emit_event(UnsupportedRecovered {
+                token,
+                to,
+                amount,
+            });
+        }
+
+        /// Get the remainder of the last allocation of the token, it is added to the next allocation.
+        #[ink(message)]
+        pub fn carried_reward(&self, token: AccountId) -> u128 {
+            return self.carried.get(&token).copied().unwrap_or(0);
+        }
+
+        /// Get the reward of the token lost by the rounding, which is never paid to the directors.
+        #[ink(message)]
+        pub fn reward_dust(&self, token: AccountId) -> u128 {
+            return self.dust.get(&token).copied().unwrap_or(0) / REWARD_PRECISION;
+        }
+
+        /// Transfer the rounding dust of the reward token(ALC or an extra reward token) to `to`.
+        #[ink(message)]
+        pub fn sweep_dust(&mut self, token: AccountId, to: AccountId) {
+            self._only_operator();
+            let amount: u128 = self.reward_dust(token);
+            assert!(amount > 0, "Boardroom: no dust to sweep");
+            let dust: u128 = self.dust.get(&token).copied().unwrap_or(0);
+            self.dust.insert(token, dust - amount * REWARD_PRECISION);
+
+            let ret: bool = if token == self.cash.to_account_id() {
+                self.cash.transfer(to, amount).is_ok()
+            } else {
+                assert!(self.reward_states.contains_key(&token), "Boardroom: reward token does not exist");
+                let mut reward_token: TokenStub = FromAccountId::from_account_id(token);
+                reward_token.transfer(to, amount).is_ok()
+            };
+            assert!(ret, "Boardroom: sweep_dust transfer err");
+
+            self.env().emit_event(DustSwept {
+                token,
+                to,
+                amount,
+            });
+        }
+
+        /// Get the epochs the withdraw is locked after the stake, the epochs the claim is locked after the claim,
+        /// and the length(ms) of an epoch.
+        #[ink(message)]
+        pub fn get_lockup(&self) -> (u32, u32, u64) {
+            return (self.withdraw_lockup_epochs, self.reward_lockup_epochs, self.lockup_period);
+        }
+
+        /// Set the lockups in epochs of `period`(ms), the period should be the epoch of the treasury.
+        /// The reward lockup should not be longer than the withdraw lockup.
+        #[ink(message)]
+        pub fn set_lockup(&mut self, withdraw_lockup_epochs: u32, reward_lockup_epochs: u32, period: u64) {
+            self._only_operator();
+            assert!(withdraw_lockup_epochs <= MAX_LOCKUP_EPOCHS, "Boardroom: lockup is too long");
+            assert!(reward_lockup_epochs <= withdraw_lockup_epochs, "Boardroom: reward lockup is longer than withdraw lockup");
+            self.withdraw_lockup_epochs = withdraw_lockup_epochs;
+            self.reward_lockup_epochs = reward_lockup_epochs;
+            self.lockup_period = period;
+        }
+
+        fn _unlock_time(&self, from: u64, epochs: u32) -> u64 {
+            let lockup: u64 = self.lockup_period.checked_mul(epochs as u64).expect("failed at _unlock_time the `boardroom` contract");
+            return from.checked_add(lockup).expect("failed at _unlock_time the `boardroom` contract");
+        }
+
+        /// Get the time(ms) from which the director can withdraw the ALS.
+        #[ink(message)]
+        pub fn withdraw_unlock_time(&self, director: AccountId) -> u64 {
+            let lockup = self._lockup_of(director);
+            return self._unlock_time(lockup.stake_time, self.withdraw_lockup_epochs);
+        }
+
+        /// Get the time(ms) from which the director can claim the reward.
+        #[ink(message)]
+        pub fn claim_unlock_time(&self, director: AccountId) -> u64 {
+            let lockup = self._lockup_of(director);
+            return self._unlock_time(lockup.claim_time, self.reward_lockup_epochs);
+        }
+
+        /// Whether the director can withdraw the ALS now.
+        #[ink(message)]
+        pub fn can_withdraw(&self, director: AccountId) -> bool {
+            return Self::env().block_timestamp() >= self.withdraw_unlock_time(director);
+        }
+
+        /// Whether the director can claim the reward now.
+        #[ink(message)]
+        pub fn can_claim_reward(&self, director: AccountId) -> bool {
+            return Self::env().block_timestamp() >= self.claim_unlock_time(director);
+        }
+
+        /// Get the guardian who can pause the operations.
+        #[ink(message)]
+        pub fn guardian(&self) -> AccountId {
+            return self.guardian;
+        }
+
+        /// Switch the guardian. Only the operator can do it.
+        #[ink(message)]
+        pub fn set_guardian(&mut self, new_guardian: AccountId) {
+            self._only_operator();
+            self.guardian = new_guardian;
+            self.env().emit_event(GuardianChanged {
+                guardian: new_guardian,
+            });
+        }
+
+        /// Whether the operation is paused.
+        #[ink(message)]
+        pub fn is_paused(&self, operation: Operation) -> bool {
+            return self.paused.get(&operation).copied().unwrap_or(false);
+        }
+
+        /// Pause the operation. Called by the guardian or the operator.
+        #[ink(message)]
+        pub fn pause(&mut self, operation: Operation) {
+            let sender = Self::env().caller();
+            assert!(sender == self.guardian || sender == self.operator, "Boardroom: caller is not the guardian");
+            self.paused.insert(operation, true);
+            self.env().emit_event(Paused {
+                operation,
+                by: sender,
+            });
+        }
+
+        /// Unpause the operation. Only the operator can do it.
+        #[ink(message)]
+        pub fn unpause(&mut self, operation: Operation) {
+            self._only_operator();
+            self.paused.take(&operation);
+            self.env().emit_event(Unpaused {
+                operation,
+                by: Self::env().caller(),
+            });
+        }
+
+        /// Get the reward(ALC) amount per ALS, including the reward streamed since the last update.
         #[ink(message)]
         pub fn reward_per_share(&self) -> u128 {
-            let index = self.latest_snapshot_index();
-            return self.board_history[index.try_into().unwrap()].reward_per_share;
+            return self._stream_reward_per_share(self.reward_per_share_stored, self.reward_rate, self.last_update_time, self.period_finish);
+        }
+
+        /// Get the time(ms) until which the reward is streamed, it is now or the period finish if it is passed.
+        #[ink(message)]
+        pub fn last_time_reward_applicable(&self) -> u64 {
+            return self._last_time_applicable(self.period_finish);
+        }
+
+        /// Get the reward(ALC) streamed per ms.
+        #[ink(message)]
+        pub fn reward_rate(&self) -> u128 {
+            return self.reward_rate;
+        }
+
+        /// Get the time(ms) when the current reward stream finishes.
+        #[ink(message)]
+        pub fn period_finish(&self) -> u64 {
+            return self.period_finish;
+        }
+
+        /// Get the time(ms) when the reward per ALS was last settled.
+        #[ink(message)]
+        pub fn last_update_time(&self) -> u64 {
+            return self.last_update_time;
+        }
+
+        /// Get the time(ms) the allocated reward is streamed over.
+        #[ink(message)]
+        pub fn reward_duration(&self) -> u64 {
+            return self.reward_duration;
+        }
+
+        /// Set the time(ms) the allocated reward is streamed over, it can't be changed while a reward is streaming.
+        #[ink(message)]
+        pub fn set_reward_duration(&mut self, duration: u64) {
+            self._only_operator();
+            assert!(duration > 0, "Boardroom: duration should above 0");
+            assert!(Self::env().block_timestamp() >= self.period_finish, "Boardroom: reward is streaming");
+            self.reward_duration = duration;
         }
 
         #[ink(message)]
@@ -339,36 +1249,59 @@ mod boardroom {
         /// User stake the ALS for the ALC reward. 
         #[ink(message)]
         pub fn stake(&mut self, amount: u128) {
-            self._check_same_sender_rented();
+            let sender = Self::env().caller();
+            self.stake_for(sender, amount);
+        }
 
-            // Calculate the reward.
+        /// Stake the caller's ALS for `beneficiary`, e.g. by a vesting contract. The ALS belongs to `beneficiary`,
+        /// and the withdraw lockup of `beneficiary` starts again, so the caller must be `beneficiary` or its approved operator.
+        #[ink(message)]
+        pub fn stake_for(&mut self, beneficiary: AccountId, amount: u128) {
+            self._when_not_paused(Operation::Stake);
             let sender = Self::env().caller();
-            self._update_reward(sender);
+            assert!(sender == beneficiary || self.is_approved_operator(beneficiary, sender), "Boardroom: caller is not approved");
+            self._guard_enter();
+
+            // Calculate the reward.
+            self._update_reward(beneficiary);
             assert!(amount > 0, "Boardroom: Cannot stake 0");
 
             // Stake the ALS
-            self._stake(amount);
+            self._stake(sender, beneficiary, amount);
+
+            // Lock the withdraw from now, and the claim too if the director never claimed.
+            let now: u64 = Self::env().block_timestamp();
+            let mut lockup = self._lockup_of(beneficiary);
+            if !self.lockups.contains_key(&beneficiary) {
+                lockup.claim_time = now;
+            }
+            lockup.stake_time = now;
+            self.lockups.insert(beneficiary, lockup);
 
             // Emit the event.
             self.env().emit_event(Staked {
-                user: Some(sender),
+                user: Some(beneficiary),
                 amount,
+                payer: sender,
             });
 
-            // Ensure stake once per block.
-            self._update_sender_rented_status();
+            // Release the guard, the action is limited to once per block.
+            self._guard_exit();
         }
 
         /// Withdraw the ALS user staked.
         #[ink(message)]
         pub fn withdraw(&mut self, amount: u128) {
-            self._check_same_sender_rented();
+            self._when_not_paused(Operation::Withdraw);
+            self._guard_enter();
             self._director_exists();
 
             // Calculate the ALC reward.
             let sender = Self::env().caller();
             self._update_reward(sender);
             assert!(amount > 0, "Boardroom: Cannot withdraw 0");
+            assert!(self.can_withdraw(sender), "Boardroom: still in withdraw lockup");
+            assert!(!self.is_locked(sender), "Boardroom: ALS is locked");
 
             // Withdraw the ALS.
             self._withdraw(amount);
@@ -378,8 +1311,194 @@ mod boardroom {
                 user: Some(sender),
                 amount,
             });
-            // Ensure stake once per block.
-            self._update_sender_rented_status();
+            // Release the guard, the action is limited to once per block.
+            self._guard_exit();
+        }
+
+        /// Get the config of the vote-escrowed ALS.
+        #[ink(message)]
+        pub fn get_lock_mode(&self) -> LockMode {
+            return self.lock_mode.clone();
+        }
+
+        /// Set the vote-escrowed ALS: whether it is enabled, the max lock time(ms) and the max boost in basis point.
+        /// The max lock time can only be changed while it is disabled. Disabling it stops all the boosts and
+        /// releases all the locks at once.
+        #[ink(message)]
+        pub fn set_lock_mode(&mut self, enabled: bool, max_duration: u64, max_boost: u128) {
+            self._only_operator();
+            assert!(!enabled || max_duration >= LOCK_STEPS, "Boardroom: max_duration is too short");
+            let now: u64 = Self::env().block_timestamp();
+            if self.lock_mode.enabled && enabled {
+                assert!(max_duration == self.lock_mode.max_duration, "Boardroom: disable the lock mode to change max_duration");
+            } else if self.lock_mode.enabled {
+                // Stop all the boosts now, the directors are re-weighted when they are settled.
+                self._checkpoint_locks();
+                self._settle_streams_until(now);
+                self.lock_disabled_at.insert(self.lock_generation, now);
+                self.lock_generation = self.lock_generation.checked_add(1).expect("failed at set_lock_mode the `boardroom` contract");
+                self.weight_total = self.stake_total;
+            } else if enabled {
+                let step: u64 = max_duration / LOCK_STEPS;
+                self.lock_checkpoint = now - now % step;
+                self.last_lock_end = 0;
+            }
+            self.lock_mode = LockMode {
+                enabled,
+                max_duration,
+                max_boost,
+            };
+        }
+
+        /// Get the time(ms) until which the account's ALS is locked.
+        #[ink(message)]
+        pub fn lock_end(&self, account: AccountId) -> u64 {
+            let lock = self._lock_of(account);
+            if lock.generation != self.lock_generation {
+                return 0;
+            }
+            return lock.end;
+        }
+
+        /// Whether the account's ALS is locked and can't be withdrawn.
+        #[ink(message)]
+        pub fn is_locked(&self, account: AccountId) -> bool {
+            return self.lock_mode.enabled && Self::env().block_timestamp() < self.lock_end(account);
+        }
+
+        /// Get the weight of the account for the reward and the voting, as of its last refresh.
+        /// A stopped boost is dropped when the account is settled.
+        #[ink(message)]
+        pub fn weight_of(&self, account: AccountId) -> u128 {
+            return self.weights.get(&account).copied().unwrap_or(0);
+        }
+
+        /// Get the weight the account would have if it is refreshed now.
+        #[ink(message)]
+        pub fn current_weight_of(&self, account: AccountId) -> u128 {
+            return self._compute_weight(account);
+        }
+
+        /// Get the total weight of the directors.
+        #[ink(message)]
+        pub fn total_weight(&self) -> u128 {
+            return self.weight_total;
+        }
+
+        /// Lock all the staked ALS for `duration`(ms) from now for the boosted weight, the lock end is rounded down
+        /// to the lock step. The lock can only be extended.
+        #[ink(message)]
+        pub fn lock(&mut self, duration: u64) {
+            self._when_not_paused(Operation::Stake);
+            assert!(self.lock_mode.enabled, "Boardroom: lock mode is disabled");
+            self._guard_enter();
+            self._director_exists();
+            assert!(duration <= self.lock_mode.max_duration, "Boardroom: lock is too long");
+
+            let sender = Self::env().caller();
+            let now: u64 = Self::env().block_timestamp();
+            let unlock_time: u64 = now.checked_add(duration).expect("failed at lock the `boardroom` contract");
+            let unlock_time: u64 = unlock_time - unlock_time % self._lock_step();
+            assert!(unlock_time > now, "Boardroom: lock is too short");
+            assert!(unlock_time > self.lock_end(sender), "Boardroom: lock can only be extended");
+
+            // Settle the reward with the old weight.
+            self._update_reward(sender);
+            let old_lock = self._lock_of(sender);
+            self._take_expiring(&old_lock);
+            let boost_rate: u128 = self.lock_mode.max_boost.checked_mul((unlock_time - now) as u128).expect("failed at lock the `boardroom` contract");
+            let lock = VoteLock {
+                end: unlock_time,
+                boost_rate: boost_rate.checked_div(self.lock_mode.max_duration as u128).expect("failed at lock the `boardroom` contract"),
+                boost: 0,
+                generation: self.lock_generation,
+            };
+            self.locks.insert(sender, lock);
+            let weight: u128 = self._update_weight(sender);
+
+            self.env().emit_event(Locked {
+                user: Some(sender),
+                unlock_time,
+                weight,
+            });
+            self._guard_exit();
+        }
+
+        /// Settle the reward of the account and drop its boost after the lock ends. Anyone can call it.
+        /// Only an account with a boost and no lock can be kicked.
+        #[ink(message)]
+        pub fn kick(&mut self, account: AccountId) {
+            self._guard_enter();
+            assert!(self.weight_of(account) > self.balance_of(account), "Boardroom: no boost to kick");
+            assert!(!self.is_locked(account), "Boardroom: ALS is locked");
+
+            self._update_reward(account);
+            let weight: u128 = self._update_weight(account);
+
+            self.env().emit_event(WeightUpdated {
+                user: Some(account),
+                weight,
+            });
+            self._guard_exit();
+        }
+
+        /// Withdraw all the staked ALS without the reward, in case the reward can't be calculated or paid.
+        /// The reward is forfeited, and allocated to the other directors with the next allocation.
+        /// The withdraw lockup is ignored but the ALS locked for the boost is not.
+        #[ink(message)]
+        pub fn emergency_withdraw(&mut self) {
+            self._when_not_paused(Operation::Withdraw);
+            self._guard_enter();
+            self._director_exists();
+
+            let sender = Self::env().caller();
+            assert!(!self.is_locked(sender), "Boardroom: ALS is locked");
+            let amount: u128 = self.balance_of(sender);
+
+            // The stopped boost is not counted in the total weight any more.
+            let lock = self._lock_of(sender);
+            let weight: u128 = if self._boost_counted(&lock) { self.weight_of(sender) } else { amount };
+            self._take_expiring(&lock);
+            self.locks.take(&sender);
+
+            // Forfeit the reward settled before the last update, the reward streamed after it is shared by the
+            // other directors as the weight is removed before it is settled.
+            let seat = self._get_director_board_seat(sender).unwrap();
+            let forfeited: u128 = self._settled_reward(weight, self.reward_per_share_stored, seat.reward_per_share_paid, seat.reward_earned);
+            let cash: AccountId = self.cash.to_account_id();
+            self._forfeit(cash, forfeited);
+            self.directors.take(&sender);
+
+            let tokens: Vec<AccountId> = self.reward_tokens.iter().copied().collect();
+            for token in tokens {
+                let state = self._reward_state(token);
+                let token_seat = self._token_seat(token, sender);
+                let token_forfeited: u128 = self._settled_reward(weight, state.reward_per_share_stored, token_seat.reward_per_share_paid, token_seat.reward_earned);
+                self._forfeit(token, token_forfeited);
+                self.token_seats.take(&(token, sender));
+            }
+
+            // Return the ALS.
+            self.weight_total = self.weight_total.saturating_sub(weight);
+            self.weights.take(&sender);
+            self.stake_total = self.stake_total.saturating_sub(amount);
+            self.balances.take(&sender);
+
+            let ret:bool = self.share.transfer(sender, amount).is_ok();
+            assert!(ret, "Boardroom: emergency_withdraw share.transfer err");
+
+            self.env().emit_event(EmergencyWithdrawn {
+                user: Some(sender),
+                amount,
+                forfeited,
+            });
+            self._guard_exit();
+        }
+
+        /// Get the forfeited reward of the token which will be allocated with the next allocation.
+        #[ink(message)]
+        pub fn forfeited_reward(&self, token: AccountId) -> u128 {
+            return self.forfeited.get(&token).copied().unwrap_or(0);
         }
 
         /// User exit system, system will return back the ALS, ALC reward.
@@ -395,47 +1514,243 @@ mod boardroom {
         #[ink(message)]
         pub fn claim_reward(&mut self) {
             let sender = Self::env().caller();
+            self._claim_reward(sender, sender);
+        }
+
+        /// User claim the ALC reward and the extra reward tokens to `recipient`.
+        #[ink(message)]
+        pub fn claim_reward_to(&mut self, recipient: AccountId) {
+            let sender = Self::env().caller();
+            self._claim_reward(sender, recipient);
+        }
+
+        /// The approved operator claims the reward of `director` to `recipient`, e.g. a helper contract which compounds
+        /// the reward for the director.
+        #[ink(message)]
+        pub fn claim_reward_for(&mut self, director: AccountId, recipient: AccountId) {
+            let sender = Self::env().caller();
+            assert!(self.is_approved_operator(director, sender), "Boardroom: caller is not approved");
+            self._claim_reward(director, recipient);
+        }
+
+        /// Approve or revoke `operator` to claim the caller's reward by `claim_reward_for`.
+        #[ink(message)]
+        pub fn approve_operator(&mut self, operator: AccountId, approved: bool) {
+            let sender = Self::env().caller();
+            assert!(sender != operator, "Boardroom: approve to caller");
+            if approved {
+                self.approvals.insert((sender, operator), true);
+            } else {
+                self.approvals.take(&(sender, operator));
+            }
+
+            self.env().emit_event(OperatorApproved {
+                owner: sender,
+                operator,
+                approved,
+            });
+        }
+
+        /// Whether `operator` is approved by `owner`.
+        #[ink(message)]
+        pub fn is_approved_operator(&self, owner: AccountId, operator: AccountId) -> bool {
+            return self.approvals.get(&(owner, operator)).copied().unwrap_or(false);
+        }
+
+        fn _claim_reward(&mut self, sender: AccountId, recipient: AccountId) {
+            self._when_not_paused(Operation::ClaimReward);
             // Caculate the reward.
             self._update_reward(sender);
             let seat = self._get_director_board_seat(sender).unwrap();
             let reward: u128 = seat.reward_earned;
+            let tokens: Vec<AccountId> = self.reward_tokens.iter().copied().collect();
+            let token_earned: bool = tokens.iter().any(|token| self._token_seat(*token, sender).reward_earned > 0);
+            if reward > 0 || token_earned {
+                assert!(self.can_claim_reward(sender), "Boardroom: still in reward lockup");
+                let mut lockup = self._lockup_of(sender);
+                lockup.claim_time = Self::env().block_timestamp();
+                self.lockups.insert(sender, lockup);
+            }
+
+            // Pay the extra reward tokens.
+            for token in tokens {
+                let mut token_seat = self._token_seat(token, sender);
+                let token_reward: u128 = token_seat.reward_earned;
+                if token_reward == 0 {
+                    continue;
+                }
+                token_seat.reward_earned = 0;
+                self.token_seats.insert((token, sender), token_seat);
+
+                let mut reward_token: TokenStub = FromAccountId::from_account_id(token);
+                let ret: bool = reward_token.transfer(recipient, token_reward).is_ok();
+                assert!(ret, "Boardroom: Cannot claim_reward token.transfer err");
+
+                self.env().emit_event(RewardTokenPaid {
+                    token,
+                    user: Some(sender),
+                    reward: token_reward,
+                    recipient,
+                });
+            }
+
             if reward > 0 {
                 let index = seat.last_snapshot_index;
-                self._update_seat(sender, 0, index);
+                self._update_seat(sender, 0, index, seat.reward_per_share_paid);
                 
                 // Return back the ALC reward to user.
-                let ret:bool = self.cash.transfer(sender, reward).is_ok();
+                let ret:bool = self.cash.transfer(recipient, reward).is_ok();
                 assert!(ret, "Boardroom: Cannot claim_reward cash.transfer err");
 
                 // Emit the event.
                 self.env().emit_event(RewardPaid {
                     user: Some(sender),
                     reward,
+                    recipient,
                 });
             }
         }
 
+        /// Get the extra reward tokens.
+        #[ink(message)]
+        pub fn reward_tokens(&self) -> Vec<AccountId> {
+            return self.reward_tokens.iter().copied().collect();
+        }
+
+        /// Get the stream of the extra reward token.
+        #[ink(message)]
+        pub fn get_reward_state(&self, token: AccountId) -> Option<RewardState> {
+            return self.reward_states.get(&token).cloned();
+        }
+
+        /// Add an extra reward token which can be allocated by the operator and `distributor`.
+        #[ink(message)]
+        pub fn add_reward_token(&mut self, token: AccountId, distributor: AccountId) {
+            self._only_operator();
+            assert!(token != self.cash.to_account_id() && token != self.share.to_account_id(), "Boardroom: protocol token can't be a reward token");
+            assert!(!self.reward_states.contains_key(&token), "Boardroom: reward token exists");
+            assert!(self.reward_tokens.len() < MAX_REWARD_TOKENS, "Boardroom: too many reward tokens");
+
+            let now: u64 = Self::env().block_timestamp();
+            self.reward_tokens.push(token);
+            self.reward_states.insert(token, RewardState {
+                distributor,
+                reward_rate: 0,
+                period_finish: 0,
+                last_update_time: now,
+                reward_per_share_stored: 0,
+                snapshot_count: 0,
+            });
+
+            self.env().emit_event(RewardTokenAdded {
+                token,
+                distributor,
+            });
+        }
+
+        /// Switch the distributor of the extra reward token.
+        #[ink(message)]
+        pub fn set_reward_distributor(&mut self, token: AccountId, distributor: AccountId) {
+            self._only_operator();
+            let mut state = self._reward_state(token);
+            state.distributor = distributor;
+            self.reward_states.insert(token, state);
+        }
+
+        /// Get the extra reward token the director can claim.
+        #[ink(message)]
+        pub fn earned_token(&self, token: AccountId, account: AccountId) -> u128 {
+            return self._token_earned(token, account);
+        }
+
+        /// Get the allocations of the extra reward token from `offset`, no more than `limit`(at most 100).
+        #[ink(message)]
+        pub fn get_token_snapshots(&self, token: AccountId, offset: u32, limit: u32) -> Vec<BoardSnapshot> {
+            let limit: u32 = if limit > MAX_PAGE_SIZE { MAX_PAGE_SIZE } else { limit };
+            let count: u32 = self._reward_state(token).snapshot_count;
+            let mut records: Vec<BoardSnapshot> = Vec::new();
+            let mut index: u32 = offset;
+            while index < count && records.len() < limit as usize {
+                records.push(self.token_history.get(&(token, index)).unwrap().clone());
+                index += 1;
+            }
+            return records;
+        }
+
+        /// Allocate the extra reward token, it is streamed over `reward_duration` like the ALC reward.
+        /// Called by the operator or the token's distributor.
+        #[ink(message)]
+        pub fn allocate_reward(&mut self, token: AccountId, amount: u128) {
+            self._when_not_paused(Operation::AllocateReward);
+            let sender = Self::env().caller();
+            self._checkpoint_locks();
+            let mut state = self._reward_state(token);
+            assert!(sender == self.operator || sender == state.distributor, "Boardroom: caller is not the distributor");
+            self._guard_enter();
+            assert!(amount > 0, "Boardroom: Cannot allocate 0");
+            assert!(self.weight_total > 0, "Boardroom: Cannot allocate when total_supply is 0");
+
+            // Settle the reward streamed at the old rate.
+            self._settle_token_stream(token, &mut state);
+            let now: u64 = Self::env().block_timestamp();
+            let reward: u128 = self._next_reward(token, amount, state.reward_rate, state.period_finish);
+            state.reward_rate = self._reward_rate(token, reward);
+            assert!(state.reward_rate > 0, "Boardroom: reward is too small to stream");
+            state.last_update_time = now;
+            state.period_finish = now.checked_add(self.reward_duration).expect("failed at allocate_reward the `boardroom` contract");
+
+            // Record the allocation for reporting.
+            let index: u32 = state.snapshot_count;
+            self.token_history.insert((token, index), BoardSnapshot {
+                time: now,
+                reward_received: amount,
+                reward_per_share: state.reward_per_share_stored,
+            });
+            state.snapshot_count = index.checked_add(1).expect("failed at allocate_reward the `boardroom` contract");
+            self.reward_states.insert(token, state);
+
+            // transfer the token from the distributor to this.
+            let this = self.env().account_id();
+            let mut reward_token: TokenStub = FromAccountId::from_account_id(token);
+            let ret: bool = reward_token.transfer_from(sender, this, amount).is_ok();
+            assert!(ret, "Boardroom: allocate_reward transfer_from is err");
+
+            self.env().emit_event(RewardTokenAllocated {
+                token,
+                user: Some(sender),
+                reward: amount,
+            });
+            self._guard_exit();
+        }
+
         /// Allocate the ALC reward. Called by treasury.
+        /// The reward is streamed to the directors linearly over `reward_duration`, the reward which is not
+        /// streamed yet is added to the new stream.
         #[ink(message)]
         pub fn allocate_seigniorage(&mut self, amount: u128) {
             self._only_operator();
-            self._check_same_sender_rented();
+            self._when_not_paused(Operation::AllocateSeigniorage);
+            self._guard_enter();
             assert!(amount > 0, "Boardroom: Cannot allocate 0");
 
-            let total: u128 = self.total_supply();
-            assert!(total > 0, "Boardroom: Cannot allocate when total_supply is 0");
+            assert!(self.weight_total > 0, "Boardroom: Cannot allocate when total_supply is 0");
+
+            // Settle the reward streamed at the old rate.
+            self._update_reward_per_share();
 
-            let one_unit_with_decimal: u128 = self.util.get_one_unit_with_decimal();
-            let prev_rps: u128 = self.reward_per_share();
-            let amount_mul: u128 = amount.checked_mul(one_unit_with_decimal).expect("failed at allocateSeigniorage the `boardroom` contract");
-            let amount_mul_div: u128 = amount_mul.checked_div(total).expect("failed at allocateSeigniorage the `boardroom` contract");
-            let next_rps: u128 = prev_rps.checked_add(amount_mul_div).expect("failed at allocateSeigniorage the `boardroom` contract");
+            let now: u64 = Self::env().block_timestamp();
+            let cash: AccountId = self.cash.to_account_id();
+            let reward: u128 = self._next_reward(cash, amount, self.reward_rate, self.period_finish);
+            self.reward_rate = self._reward_rate(cash, reward);
+            assert!(self.reward_rate > 0, "Boardroom: reward is too small to stream");
+            self.last_update_time = now;
+            self.period_finish = now.checked_add(self.reward_duration).expect("failed at allocateSeigniorage the `boardroom` contract");
 
-            // Update the reward per ALS.
+            // Record the allocation for reporting.
             let snapshot = BoardSnapshot {
-                time: Self::env().block_timestamp(),
+                time: now,
                 reward_received: amount,
-                reward_per_share: next_rps,
+                reward_per_share: self.reward_per_share_stored,
             };
             self.board_history.push(snapshot);
 
@@ -451,8 +1766,8 @@ mod boardroom {
                 user: Some(sender),
                 reward: amount,
             });
-            // Ensure stake once per block.
-            self._update_sender_rented_status();
+            // Release the guard, the action is limited to once per block.
+            self._guard_exit();
         }
     }
 }
@@ -7,4 +7,5 @@ cargo +nightly contract build --manifest-path asset/Cargo.toml
 cargo +nightly contract build --manifest-path oracle/Cargo.toml
 cargo +nightly contract build --manifest-path distributor/Cargo.toml
 cargo +nightly contract build --manifest-path boardroom/Cargo.toml
-cargo +nightly contract build --manifest-path treasury/Cargo.toml
\ No newline at end of file
+cargo +nightly contract build --manifest-path treasury/Cargo.toml
+cargo +nightly contract build --manifest-path vault/Cargo.toml
\ No newline at end of file
@@ -29,7 +29,7 @@ mod distributor {
         traits::{PackedLayout, SpreadLayout},
         Lazy,
     };
-    use ink_env::call::FromAccountId;
+    use ink_env::call::{FromAccountId, ToAccountId};
     use asset::Asset;
     use tokenstub::TokenStub;
 
@@ -44,12 +44,26 @@ mod distributor {
         pub amount: Balance,
     }
 
+    /// The operations of the distributor which can be paused by the guardian.
+    #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, scale::Encode, scale::Decode, SpreadLayout, PackedLayout)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink_storage::traits::StorageLayout)
+    )]
+    pub enum Operation {
+        DepositToken,
+        DepositCoin,
+        DistributeAlc,
+    }
+
     #[ink(storage)]
     pub struct Distributor {
         cash: Lazy<Asset>,
         a_usd: Lazy<TokenStub>,
 
         operator: AccountId,
+        guardian: AccountId,
+        paused: StorageHashMap<Operation, bool>,
         deposit_records: StorageHashMap<AccountId, Balance>,
     }
 
@@ -78,6 +92,41 @@ mod distributor {
         count: u32,
     }
 
+    /// Event emitted when the guardian or the operator pause an operation.
+    #[ink(event)]
+    pub struct Paused {
+        #[ink(topic)]
+        operation: Operation,
+        #[ink(topic)]
+        by: AccountId,
+    }
+
+    /// Event emitted when the operator unpause an operation.
+    #[ink(event)]
+    pub struct Unpaused {
+        #[ink(topic)]
+        operation: Operation,
+        #[ink(topic)]
+        by: AccountId,
+    }
+
+    /// Event emitted when the operator recover the token sent to this contract by mistake.
+    #[ink(event)]
+    pub struct UnsupportedRecovered {
+        #[ink(topic)]
+        token: AccountId,
+        #[ink(topic)]
+        to: AccountId,
+        amount: u128,
+    }
+
+    /// Event emitted when the operator switch the guardian.
+    #[ink(event)]
+    pub struct GuardianChanged {
+        #[ink(topic)]
+        guardian: AccountId,
+    }
+
     impl Distributor {
         /// Create the new distributor with the ALC or aUsd's address.
         #[ink(constructor)]
@@ -91,6 +140,8 @@ mod distributor {
                 cash: Lazy::new(cash),
                 a_usd: Lazy::new(a_usd),
                 operator: sender,
+                guardian: sender,
+                paused: StorageHashMap::new(),
                 deposit_records: StorageHashMap::new(),
             };
             instance
@@ -102,6 +153,11 @@ mod distributor {
             assert!(self.operator == sender, "Distributor: caller is not the operator");
         }
 
+        /// Ensure the operation is not paused.
+        fn _when_not_paused(&self, operation: Operation) {
+            assert!(!self.is_paused(operation), "Distributor: operation is paused");
+        }
+
         /// Update or insert the deposti record.
         fn _upsert_deposit_record(&mut self, user:AccountId, amount:Balance) {
             let b = self.deposit_records.get(&user).copied().unwrap_or(0);
@@ -139,9 +195,71 @@ mod distributor {
             self.operator = new_operator;
         }
 
+        /// Get the guardian who can pause the operations.
+        #[ink(message)]
+        pub fn guardian(&self) -> AccountId {
+            return self.guardian;
+        }
+
+        /// Switch the guardian. Only the operator can do it.
+        #[ink(message)]
+        pub fn set_guardian(&mut self, new_guardian: AccountId) {
+            self._only_operator();
+            self.guardian = new_guardian;
+            self.env().emit_event(GuardianChanged {
+                guardian: new_guardian,
+            });
+        }
+
+        /// Whether the operation is paused.
+        #[ink(message)]
+        pub fn is_paused(&self, operation: Operation) -> bool {
+            return self.paused.get(&operation).copied().unwrap_or(false);
+        }
+
+        /// Pause the operation. Called by the guardian or the operator.
+        #[ink(message)]
+        pub fn pause(&mut self, operation: Operation) {
+            let sender = Self::env().caller();
+            assert!(sender == self.guardian || sender == self.operator, "Distributor: caller is not the guardian");
+            self.paused.insert(operation, true);
+            self.env().emit_event(Paused {
+                operation,
+                by: sender,
+            });
+        }
+
+        /// Unpause the operation. Only the operator can do it.
+        #[ink(message)]
+        pub fn unpause(&mut self, operation: Operation) {
+            self._only_operator();
+            self.paused.take(&operation);
+            self.env().emit_event(Unpaused {
+                operation,
+                by: Self::env().caller(),
+            });
+        }
+
+        /// Transfer the token sent to this contract by mistake to `to`. ALC and aUsd can't be recovered.
+        #[ink(message)]
+        pub fn recover_unsupported(&mut self, token: AccountId, amount: u128, to: AccountId) {
+            self._only_operator();
+            assert!(token != self.cash.to_account_id() && token != self.a_usd.to_account_id(), "Distributor: protocol token can't be recovered");
+            let mut unsupported: TokenStub = FromAccountId::from_account_id(token);
+            let ret: bool = unsupported.transfer(to, amount).is_ok();
+            assert!(ret, "Distributor: recover_unsupported transfer err");
+
+            self.env().emit_event(UnsupportedRecovered {
+                token,
+                to,
+                amount,
+            });
+        }
+
         /// Deposit the erc20 token just like aUsd.
         #[ink(message)]
         pub fn deposit_token(&mut self, amount:Balance) {
+            self._when_not_paused(Operation::DepositToken);
             let user:AccountId = self.env().caller();
             assert!(user != AccountId::from([0; 32]), "Distributor: distribute_alc err");
             assert!(amount > 0, "Distributor: deposit err");
@@ -164,6 +282,7 @@ mod distributor {
         /// Deposit the coin of the chain.
         #[ink(message, payable)]
         pub fn deposit_coin(&mut self) {
+            self._when_not_paused(Operation::DepositCoin);
             let caller = self.env().caller();
             assert!(caller != AccountId::from([0; 32]), "Distributor: distribute_alc err");
 
@@ -182,6 +301,7 @@ mod distributor {
         #[ink(message)]
         pub fn distribute_alc(&mut self, records:Vec<Record>) {
             self._only_operator();
+            self._when_not_paused(Operation::DistributeAlc);
 
             let a: usize = records.len();
             assert!(a > 0, "Distributor: distribute_alc err");
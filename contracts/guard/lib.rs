@@ -0,0 +1,185 @@
+// Copyright 2018-2021 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+//! The guard shared by the contracts: a reentrancy lock and a limit of one action per block for each account
+//! in each scope, e.g. each pool of the treasury.
+//!
+//! It replaces the `status: HashMap<(u32, AccountId), bool>` which recorded every (block, caller) forever,
+//! only the last action block of each (account, scope) is kept now.
+
+use ink_env::AccountId;
+use ink_storage::{
+    collections::HashMap as StorageHashMap,
+    traits::SpreadLayout,
+};
+
+/// The scope of the contracts which limit an account to one action per block in total.
+pub const DEFAULT_SCOPE: u32 = 0;
+
+/// Guard record whether a guarded message is running and the last block each account acted in each scope.
+#[derive(SpreadLayout)]
+#[cfg_attr(feature = "std", derive(ink_storage::traits::StorageLayout))]
+pub struct Guard {
+    locked: bool,
+    last_action: StorageHashMap<(AccountId, u32), u32>,
+}
+
+impl Guard {
+    /// Create an unlocked guard without any action.
+    pub fn new() -> Self {
+        Self {
+            locked: false,
+            last_action: StorageHashMap::new(),
+        }
+    }
+
+    /// Whether a guarded message is running.
+    pub fn locked(&self) -> bool {
+        return self.locked;
+    }
+
+    /// Get the last block the account acted in.
+    pub fn last_action(&self, account: &AccountId) -> Option<u32> {
+        return self.last_scope_action(account, DEFAULT_SCOPE);
+    }
+
+    /// Get the last block the account acted in the scope.
+    pub fn last_scope_action(&self, account: &AccountId, scope: u32) -> Option<u32> {
+        return self.last_action.get(&(*account, scope)).copied();
+    }
+
+    /// Whether the account has acted in the block.
+    pub fn acted(&self, account: &AccountId, block: u32) -> bool {
+        return self.acted_in_scope(account, DEFAULT_SCOPE, block);
+    }
+
+    /// Whether the account has acted in the scope in the block.
+    pub fn acted_in_scope(&self, account: &AccountId, scope: u32, block: u32) -> bool {
+        return self.last_scope_action(account, scope) == Some(block);
+    }
+
+    /// Lock the guard and record the action of the account in the block.
+    /// Panic if a guarded message is running or the account has acted in the block.
+    pub fn enter(&mut self, account: AccountId, block: u32) {
+        self.enter_scope(account, DEFAULT_SCOPE, block);
+    }
+
+    /// Lock the guard and record the action of the account in the scope in the block.
+    /// Panic if a guarded message is running or the account has acted in the scope in the block.
+    pub fn enter_scope(&mut self, account: AccountId, scope: u32, block: u32) {
+        assert!(!self.locked, "Guard: reentrant call");
+        assert!(!self.acted_in_scope(&account, scope, block), "Guard: one action per block");
+        self.locked = true;
+        self.last_action.insert((account, scope), block);
+    }
+
+    /// Unlock the guard when the guarded message is done.
+    pub fn exit(&mut self) {
+        self.locked = false;
+    }
+}
+
+impl Default for Guard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn enter_and_exit_works() {
+        let mut guard = Guard::new();
+        let alice = AccountId::from([0x01; 32]);
+
+        assert!(!guard.locked());
+        guard.enter(alice, 1);
+        assert!(guard.locked());
+        assert_eq!(guard.last_action(&alice), Some(1));
+        guard.exit();
+        assert!(!guard.locked());
+
+        // the next block is allowed.
+        guard.enter(alice, 2);
+        guard.exit();
+        assert_eq!(guard.last_action(&alice), Some(2));
+        assert!(!guard.acted(&alice, 1));
+    }
+
+    #[test]
+    fn other_account_in_same_block_works() {
+        let mut guard = Guard::new();
+        let alice = AccountId::from([0x01; 32]);
+        let bob = AccountId::from([0x02; 32]);
+
+        guard.enter(alice, 1);
+        guard.exit();
+        guard.enter(bob, 1);
+        guard.exit();
+        assert!(guard.acted(&alice, 1));
+        assert!(guard.acted(&bob, 1));
+    }
+
+    #[test]
+    fn other_scope_in_same_block_works() {
+        let mut guard = Guard::new();
+        let alice = AccountId::from([0x01; 32]);
+
+        guard.enter_scope(alice, 0, 1);
+        guard.exit();
+        guard.enter_scope(alice, 1, 1);
+        guard.exit();
+        assert!(guard.acted_in_scope(&alice, 0, 1));
+        assert!(guard.acted_in_scope(&alice, 1, 1));
+        assert!(!guard.acted_in_scope(&alice, 2, 1));
+        assert_eq!(guard.last_action(&alice), Some(1));
+    }
+
+    #[test]
+    #[should_panic(expected = "Guard: one action per block")]
+    fn same_scope_action_fails() {
+        let mut guard = Guard::new();
+        let alice = AccountId::from([0x01; 32]);
+
+        guard.enter_scope(alice, 1, 1);
+        guard.exit();
+        guard.enter_scope(alice, 1, 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "Guard: reentrant call")]
+    fn reentrant_call_fails() {
+        let mut guard = Guard::new();
+        let alice = AccountId::from([0x01; 32]);
+        let bob = AccountId::from([0x02; 32]);
+
+        guard.enter(alice, 1);
+        guard.enter(bob, 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "Guard: one action per block")]
+    fn same_block_action_fails() {
+        let mut guard = Guard::new();
+        let alice = AccountId::from([0x01; 32]);
+
+        guard.enter(alice, 1);
+        guard.exit();
+        guard.enter(alice, 1);
+    }
+}
@@ -25,6 +25,10 @@ mod oracle {
         operator: AccountId,
         cash_price: u128,
         last_update_time_stamp: u32,
+        share_price: u128,
+        share_last_update_time_stamp: u32,
+        target_price: u128,
+        target_last_update_time_stamp: u32,
     }
 
     impl Oracle {
@@ -36,6 +40,10 @@ mod oracle {
                 operator: sender,
                 cash_price: 0,
                 last_update_time_stamp: 0,
+                share_price: 0,
+                share_last_update_time_stamp: 0,
+                target_price: 0,
+                target_last_update_time_stamp: 0,
             }
         }
 
@@ -73,6 +81,38 @@ mod oracle {
             self.cash_price = price;
             self.last_update_time_stamp = ts;
         }
+
+        /// Get the ALS's price.
+        #[ink(message)]
+        pub fn get_share_price(&self) -> u128 {
+            self.share_price
+        }
+
+        /// Update the ALS's price.
+        #[ink(message)]
+        pub fn update_share_price(&mut self, price: u128, ts: u32) {
+            self._only_operator();
+
+            assert!(ts - self.share_last_update_time_stamp > 1, "invalid time stamp");
+            self.share_price = price;
+            self.share_last_update_time_stamp = ts;
+        }
+
+        /// Get the target price which the ALC is pegged to, e.g. a CPI or EUR index.
+        #[ink(message)]
+        pub fn get_target_price(&self) -> u128 {
+            self.target_price
+        }
+
+        /// Update the target price.
+        #[ink(message)]
+        pub fn update_target_price(&mut self, price: u128, ts: u32) {
+            self._only_operator();
+
+            assert!(ts - self.target_last_update_time_stamp > 1, "invalid time stamp");
+            self.target_price = price;
+            self.target_last_update_time_stamp = ts;
+        }
     }
 
     #[cfg(test)]
@@ -88,5 +128,24 @@ mod oracle {
             oracle.update_cash_price(123, 123);
             assert_eq!(oracle.get_cash_price(), 123);
         }
+
+        #[ink::test]
+        fn update_and_get_share_works() {
+            let mut oracle = Oracle::new();
+
+            assert_eq!(oracle.get_share_price(), 0);
+            oracle.update_share_price(456, 123);
+            assert_eq!(oracle.get_share_price(), 456);
+            assert_eq!(oracle.get_cash_price(), 0);
+        }
+
+        #[ink::test]
+        fn update_and_get_target_works() {
+            let mut oracle = Oracle::new();
+
+            assert_eq!(oracle.get_target_price(), 0);
+            oracle.update_target_price(789, 123);
+            assert_eq!(oracle.get_target_price(), 789);
+        }
     }
 }
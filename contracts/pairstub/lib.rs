@@ -0,0 +1,51 @@
+// Copyright 2018-2021 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+pub use self::pairstub::PairStub;
+use ink_lang as ink;
+
+/// The interface of the AMM pair which the vault swaps through.
+#[ink::contract]
+mod pairstub {
+    #[ink(storage)]
+    pub struct PairStub {}
+
+    impl PairStub {
+        #[ink(constructor)]
+        pub fn new() -> Self {
+            unimplemented!()
+        }
+
+        /// Get the amount of the other token out for `amount_in` of `token_in`.
+        #[ink(message)]
+        pub fn get_amount_out(&self, _token_in: AccountId, _amount_in: Balance) -> Balance {
+            unimplemented!()
+        }
+
+        /// Take `amount_in` of `token_in` from the caller by `transfer_from`, and pay the other token to `to`.
+        /// Fails if the amount out is less than `min_amount_out`.
+        #[ink(message)]
+        pub fn swap(
+            &mut self,
+            _token_in: AccountId,
+            _amount_in: Balance,
+            _min_amount_out: Balance,
+            _to: AccountId,
+        ) -> Balance {
+            unimplemented!()
+        }
+    }
+}
@@ -18,258 +18,2212 @@ use ink_lang as ink;
 
 #[ink::contract]
 mod treasury {
-    use ink_env::call::FromAccountId;
+    use ink_prelude::vec::Vec;
+    use ink_env::call::{FromAccountId, ToAccountId};
     use ink_storage::{
         collections::HashMap,
         lazy::Lazy,
+        traits::{PackedLayout, SpreadLayout},
     };
 
     use util::Util;
     use oracle::Oracle;
-    use asset::Asset;
-    use boardroom::Boardroom;
+    use asset::{Asset, AssetOperation};
+    use boardroom::{Boardroom, BoardroomOperation};
+    use tokenstub::TokenStub;
+    use guard::Guard;
+
+    /// The delay(ms) between queueing a migration and executing it.
+    const MIGRATION_DELAY: u64 = 2 * 24 * 60 * 60 * 1000;
+
+    /// The default length(ms) of an epoch.
+    const DEFAULT_PERIOD: u64 = 24 * 60 * 60 * 1000;
+
+    /// The denominator of the basis point.
+    const BASIS_POINT: u128 = 10000;
+
+    /// The default ceiling price relative to the target price, in basis point.
+    const DEFAULT_CEILING_RATE: u128 = 10500;
+
+    /// The max basis point of the seigniorage paid to the keeper.
+    const MAX_KEEPER_REWARD_RATE: u128 = 500;
+
+    /// The max basis point of the ALS supply which can be sold in an epoch.
+    const MAX_SHARE_INFLATION_RATE: u128 = 1000;
+
+    /// The max number of the epoch records returned by a query.
+    const MAX_PAGE_SIZE: u32 = 100;
+
+    /// The operations of the treasury which can be paused by the guardian.
+    #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, scale::Encode, scale::Decode, SpreadLayout, PackedLayout)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink_storage::traits::StorageLayout)
+    )]
+    pub enum Operation {
+        BuyBonds,
+        RedeemBonds,
+        AllocateSeigniorage,
+        BuybackCash,
+        MintFractional,
+        RedeemFractional,
+        SellShare,
+    }
+
+    /// The error types of the bond purchase and redemption.
+    #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum Error {
+        /// Returned if the transaction lands after the deadline.
+        Expired,
+        /// Returned if the cash's price is above the price the user accepts.
+        PriceMoved,
+        /// Returned if the bonds bought are less than the user accepts.
+        InsufficientBondOut,
+        /// Returned if the cash redeemed is less than the user accepts.
+        InsufficientCashOut,
+    }
+
+    /// The treasury result type.
+    pub type Result<T> = core::result::Result<T, Error>;
+
+    /// Pool record a pegged cash token managed by the treasury: the (cash, bond, oracle, boardroom) set,
+    /// the target price and its own bond cap, seigniorage accounting, keeper rewards, ALS sale and epoch state.
+    #[derive(Debug, PartialEq, Eq, Clone, scale::Encode, scale::Decode, SpreadLayout, PackedLayout)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink_storage::traits::StorageLayout)
+    )]
+    pub struct Pool {
+        pub cash: AccountId,
+        pub bond: AccountId,
+        pub oracle: AccountId,
+        pub boardroom: AccountId,
+        pub target_feed: Option<AccountId>,
+        pub ceiling_rate: u128,
+        pub bond_cap: u128,
+        pub accumulated_seigniorage: u128,
+        pub next_allocation_epoch: u32,
+        pub keeper_rewards_paid: u128,
+        pub share_sale_trigger: ShareSaleTrigger,
+        pub auction_bonds_owed: u128,
+    }
+
+    /// Buyback record the config and the state of the ALC buyback.
+    /// When the ALC's price is below the floor price, which is `floor_rate` basis point of the target price,
+    /// the treasury buy back the ALC with the aUSD reserve at `price` and burn it, no more than `epoch_cap` ALC per epoch.
+    #[derive(Debug, PartialEq, Eq, Clone, Default, scale::Encode, scale::Decode, SpreadLayout, PackedLayout)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink_storage::traits::StorageLayout)
+    )]
+    pub struct Buyback {
+        pub floor_rate: u128,
+        pub price: u128,
+        pub epoch_cap: u128,
+        pub epoch: u32,
+        pub bought: u128,
+        pub reserve: u128,
+    }
+
+    /// Collateral record the config and the state of the partially collateralized ALC.
+    /// `ratio` is the basis point of the ALC's value backed by the aUSD, the rest is backed by ALS.
+    /// The ratio moves by `step` once per epoch according to the ALC's price.
+    #[derive(Debug, PartialEq, Eq, Clone, Default, scale::Encode, scale::Decode, SpreadLayout, PackedLayout)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink_storage::traits::StorageLayout)
+    )]
+    pub struct Collateral {
+        pub enabled: bool,
+        pub ratio: u128,
+        pub step: u128,
+        pub next_refresh_epoch: u32,
+        pub reserve: u128,
+    }
+
+    /// Auction record the config and the state of the Dutch auction of the bonds.
+    /// When it is enabled, each epoch's bond capacity is sold through a round whose price(ALC per ALB) descends
+    /// from `start_rate` to `floor_rate`, in basis point of one ALC, over `duration`(ms).
+    /// The bonds sold but not claimed yet are counted against the bond cap of the next round.
+    #[derive(Debug, PartialEq, Eq, Clone, Default, scale::Encode, scale::Decode, SpreadLayout, PackedLayout)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink_storage::traits::StorageLayout)
+    )]
+    pub struct Auction {
+        pub enabled: bool,
+        pub start_rate: u128,
+        pub floor_rate: u128,
+        pub duration: u64,
+        pub last_epoch: Option<u32>,
+    }
+
+    /// AuctionRound record a round of the Dutch auction. The bids are settled at the uniform `clearing_price`,
+    /// which is set when the capacity is filled, otherwise it is the price at `end_time`.
+    /// `settled` is set when the bonds sold by the round are counted as owed to the bidders.
+    #[derive(Debug, PartialEq, Eq, Clone, Default, scale::Encode, scale::Decode, SpreadLayout, PackedLayout)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink_storage::traits::StorageLayout)
+    )]
+    pub struct AuctionRound {
+        pub capacity: u128,
+        pub start_time: u64,
+        pub end_time: u64,
+        pub committed: u128,
+        pub clearing_price: u128,
+        pub settled: bool,
+    }
+
+    /// ShareSaleTrigger record whether the pool's ALS dilution sale is open: when the cash's price is below
+    /// `trigger_rate` basis point of the target price, the treasury mint and sell the ALS for the cash.
+    #[derive(Debug, PartialEq, Eq, Clone, Default, scale::Encode, scale::Decode, SpreadLayout, PackedLayout)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink_storage::traits::StorageLayout)
+    )]
+    pub struct ShareSaleTrigger {
+        pub enabled: bool,
+        pub trigger_rate: u128,
+    }
+
+    /// ShareSale record the cap and the state of the ALS dilution sale, the last-resort contraction. The cap is
+    /// shared by all the pools: no more than `epoch_cap` ALS and `max_inflation_rate` basis point of the ALS supply
+    /// are sold per epoch.
+    #[derive(Debug, PartialEq, Eq, Clone, Default, scale::Encode, scale::Decode, SpreadLayout, PackedLayout)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink_storage::traits::StorageLayout)
+    )]
+    pub struct ShareSale {
+        pub epoch_cap: u128,
+        pub max_inflation_rate: u128,
+        pub epoch: u32,
+        pub epoch_supply: u128,
+        pub sold: u128,
+        pub total_sold: u128,
+    }
+
+    /// EpochRecord record what happened to a pool in an epoch: the cash's price and the seigniorage minted when it
+    /// is allocated, how the seigniorage is split, and the bonds sold and redeemed in the epoch.
+    #[derive(Debug, PartialEq, Eq, Clone, Default, scale::Encode, scale::Decode, SpreadLayout, PackedLayout)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink_storage::traits::StorageLayout)
+    )]
+    pub struct EpochRecord {
+        pub cash_price: u128,
+        pub seigniorage: u128,
+        pub keeper_reward: u128,
+        pub treasury_reserve: u128,
+        pub boardroom_reserve: u128,
+        pub dao_fund_reserve: u128,
+        pub bonds_sold: u128,
+        pub bonds_redeemed: u128,
+    }
+
+    /// BondLimit record the limits of the bond purchase of a pool. An account can spend no more than `account_cap`
+    /// cash on the bonds per epoch, 0 means no limit. In the first `allowlist_duration`(ms) of each epoch,
+    /// only the allowlisted accounts can buy the bonds.
+    #[derive(Debug, PartialEq, Eq, Clone, Default, scale::Encode, scale::Decode, SpreadLayout, PackedLayout)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink_storage::traits::StorageLayout)
+    )]
+    pub struct BondLimit {
+        pub account_cap: u128,
+        pub allowlist_duration: u64,
+    }
 
     #[ink(storage)]
     pub struct Treasury {
-        bond_cap: u128,
+        operator: AccountId,
+        guardian: AccountId,
+        paused: HashMap<Operation, bool>,
+
+        migration_target: Option<AccountId>,
+        migration_eta: u64,
+        migrated: bool,
+        seigniorage_imported: HashMap<u32, bool>,
+
+        start_time: u64,
+        period: u64,
+        epoch_offset: u32,
+
+        keeper_reward_fixed: u128,
+        keeper_reward_rate: u128,
+
+        dao_fund: Option<AccountId>,
+
+        a_usd: Option<AccountId>,
+        buybacks: HashMap<u32, Buyback>,
+        collaterals: HashMap<u32, Collateral>,
+
+        auctions: HashMap<u32, Auction>,
+        auction_rounds: HashMap<(u32, u32), AuctionRound>,
+        auction_bids: HashMap<(u32, u32, AccountId), u128>,
+
+        bond_limits: HashMap<u32, BondLimit>,
+        bond_allowlist: HashMap<(u32, AccountId), bool>,
+        bond_purchases: HashMap<(u32, AccountId), (u32, u128)>,
+
+        epoch_records: HashMap<(u32, u32), EpochRecord>,
+
+        pools: HashMap<u32, Pool>,
+        pool_count: u32,
+
+        share_sale: ShareSale,
+
+        util:  Lazy<Util>,
+        share: Lazy<Asset>,
+
+        guard: Guard,
+    }
+
+    /// Event emitted when an redeem_bonds occurs that user redeem the ALB.
+    #[ink(event)]
+    pub struct RedeemedBonds {
+        #[ink(topic)]
+        pool_id: u32,
+        #[ink(topic)]
+        from: Option<AccountId>,
+        #[ink(topic)]
+        amount: u128,
+    }
+
+    /// Event emitted when an buy_bonds occurs that user buy the ALB.
+    #[ink(event)]
+    pub struct BoughtBonds {
+        #[ink(topic)]
+        pool_id: u32,
+        #[ink(topic)]
+        from: Option<AccountId>,
+        #[ink(topic)]
+        amount: u128,
+    }
+
+    /// Event emitted when an allocate_seigniorage occurs that system allocate the reward.
+    #[ink(event)]
+    pub struct TreasuryFunded {
+        #[ink(topic)]
+        pool_id: u32,
+        #[ink(topic)]
+        timestamp: u64,
+        #[ink(topic)]
+        seigniorage: u128,
+    }
+
+    /// Event emitted when an allocate_seigniorage occurs that system allocate the reward.
+    #[ink(event)]
+    pub struct BoardroomFunded {
+        #[ink(topic)]
+        pool_id: u32,
+        #[ink(topic)]
+        timestamp: u64,
+        #[ink(topic)]
+        seigniorage: u128,
+    }
+
+    /// Event emitted when an allocate_seigniorage occurs that the keeper is paid for calling it.
+    #[ink(event)]
+    pub struct KeeperRewarded {
+        #[ink(topic)]
+        pool_id: u32,
+        #[ink(topic)]
+        keeper: AccountId,
+        #[ink(topic)]
+        epoch: u32,
+        reward: u128,
+    }
+
+    /// Event emitted when an allocate_seigniorage occurs that no ALS is staked in the boardroom,
+    /// the boardroom's share is sent to the DAO fund, or kept as the treasury reserve when `fund` is None.
+    #[ink(event)]
+    pub struct BoardroomFundsRerouted {
+        #[ink(topic)]
+        pool_id: u32,
+        #[ink(topic)]
+        timestamp: u64,
+        #[ink(topic)]
+        fund: Option<AccountId>,
+        seigniorage: u128,
+    }
+
+    /// Event emitted when a buyback_cash occurs that the treasury buy back the user's ALC with aUSD.
+    #[ink(event)]
+    pub struct CashBoughtBack {
+        #[ink(topic)]
+        pool_id: u32,
+        #[ink(topic)]
+        from: Option<AccountId>,
+        #[ink(topic)]
+        amount: u128,
+        paid: u128,
+    }
+
+    /// Event emitted when the aUSD reserve of the buyback is changed.
+    #[ink(event)]
+    pub struct BuybackReserveChanged {
+        #[ink(topic)]
+        pool_id: u32,
+        #[ink(topic)]
+        by: AccountId,
+        reserve: u128,
+    }
+
+    /// Event emitted when the collateral ratio is adjusted according to the ALC's price.
+    #[ink(event)]
+    pub struct CollateralRatioUpdated {
+        #[ink(topic)]
+        pool_id: u32,
+        #[ink(topic)]
+        epoch: u32,
+        #[ink(topic)]
+        ratio: u128,
+    }
+
+    /// Event emitted when a mint_fractional occurs that user mint the ALC with aUSD and ALS.
+    #[ink(event)]
+    pub struct MintedFractional {
+        #[ink(topic)]
+        pool_id: u32,
+        #[ink(topic)]
+        from: Option<AccountId>,
+        #[ink(topic)]
+        amount: u128,
+        collateral: u128,
+        share: u128,
+    }
+
+    /// Event emitted when a redeem_fractional occurs that user redeem the ALC for aUSD and ALS.
+    #[ink(event)]
+    pub struct RedeemedFractional {
+        #[ink(topic)]
+        pool_id: u32,
+        #[ink(topic)]
+        from: Option<AccountId>,
+        #[ink(topic)]
+        amount: u128,
+        collateral: u128,
+        share: u128,
+    }
+
+    /// Event emitted when a round of the bond auction is opened.
+    #[ink(event)]
+    pub struct AuctionOpened {
+        #[ink(topic)]
+        pool_id: u32,
+        #[ink(topic)]
+        epoch: u32,
+        capacity: u128,
+    }
+
+    /// Event emitted when a bid_bonds occurs that user commit the cash to the bond auction.
+    #[ink(event)]
+    pub struct BondsBid {
+        #[ink(topic)]
+        pool_id: u32,
+        #[ink(topic)]
+        from: Option<AccountId>,
+        #[ink(topic)]
+        epoch: u32,
+        amount: u128,
+        price: u128,
+    }
+
+    /// Event emitted when a claim_auction_bonds occurs that user receive the bonds at the clearing price.
+    #[ink(event)]
+    pub struct AuctionBondsClaimed {
+        #[ink(topic)]
+        pool_id: u32,
+        #[ink(topic)]
+        from: Option<AccountId>,
+        #[ink(topic)]
+        epoch: u32,
+        amount: u128,
+        price: u128,
+    }
+
+    /// Event emitted when a sell_share occurs that user buy the newly minted ALS with the cash.
+    #[ink(event)]
+    pub struct ShareSold {
+        #[ink(topic)]
+        pool_id: u32,
+        #[ink(topic)]
+        from: Option<AccountId>,
+        #[ink(topic)]
+        epoch: u32,
+        amount: u128,
+        share: u128,
+    }
+
+    /// Event emitted when the operator change the cap of the ALS dilution sale.
+    #[ink(event)]
+    pub struct ShareSaleChanged {
+        epoch_cap: u128,
+        max_inflation_rate: u128,
+    }
+
+    /// Event emitted when the operator open or close the pool's ALS dilution sale.
+    #[ink(event)]
+    pub struct ShareSaleTriggerChanged {
+        #[ink(topic)]
+        pool_id: u32,
+        #[ink(topic)]
+        enabled: bool,
+        trigger_rate: u128,
+    }
+
+    /// Event emitted when the operator recover the token sent to this contract by mistake.
+    #[ink(event)]
+    pub struct UnsupportedRecovered {
+        #[ink(topic)]
+        token: AccountId,
+        #[ink(topic)]
+        to: AccountId,
+        amount: u128,
+    }
+
+    /// Event emitted when the operator add a new pegged cash token.
+    #[ink(event)]
+    pub struct PoolAdded {
+        #[ink(topic)]
+        pool_id: u32,
+        #[ink(topic)]
+        cash: AccountId,
+        bond: AccountId,
+        oracle: AccountId,
+        boardroom: AccountId,
+    }
+
+    /// Event emitted when the guardian or the operator pause an operation.
+    #[ink(event)]
+    pub struct Paused {
+        #[ink(topic)]
+        operation: Operation,
+        #[ink(topic)]
+        by: AccountId,
+    }
+
+    /// Event emitted when the operator unpause an operation.
+    #[ink(event)]
+    pub struct Unpaused {
+        #[ink(topic)]
+        operation: Operation,
+        #[ink(topic)]
+        by: AccountId,
+    }
+
+    /// Event emitted when the operator switch the guardian.
+    #[ink(event)]
+    pub struct GuardianChanged {
+        #[ink(topic)]
+        guardian: AccountId,
+    }
+
+    /// Event emitted when the operator queue the migration to a new treasury.
+    #[ink(event)]
+    pub struct MigrationQueued {
+        #[ink(topic)]
+        target: AccountId,
+        #[ink(topic)]
+        eta: u64,
+    }
+
+    /// Event emitted when the operator cancel the queued migration.
+    #[ink(event)]
+    pub struct MigrationCancelled {
+        #[ink(topic)]
+        target: AccountId,
+    }
+
+    /// Event emitted when the operator import the accumulated seigniorage handed over by the old treasury.
+    #[ink(event)]
+    pub struct SeigniorageImported {
+        #[ink(topic)]
+        pool_id: u32,
+        amount: u128,
+    }
+
+    /// Event emitted when a pool of the treasury is migrated to the new treasury.
+    #[ink(event)]
+    pub struct Migrated {
+        #[ink(topic)]
+        pool_id: u32,
+        #[ink(topic)]
+        target: AccountId,
+        cash_reserve: u128,
+        bond_reserve: u128,
+        a_usd_reserve: u128,
         accumulated_seigniorage: u128,
+    }
+
+    impl Treasury {
+        /// Create new treasury, the (cash, bond, oracle, boardroom) set is added as the pool 0.
+        #[ink(constructor)]
+        pub fn new(util_address:AccountId,
+                   cash_address:AccountId,
+                   bond_address: AccountId,
+                   share_address: AccountId,
+                   oracle_address: AccountId,
+                   boardroom_address: AccountId) -> Self {
+
+            let util: Util = FromAccountId::from_account_id(util_address);
+            let share: Asset = FromAccountId::from_account_id(share_address);
+
+            let sender = Self::env().caller();
+            let mut instance = Self {
+                operator: sender,
+                guardian: sender,
+                paused: HashMap::new(),
+
+                migration_target: None,
+                migration_eta: 0,
+                migrated: false,
+                seigniorage_imported: HashMap::new(),
+
+                start_time: Self::env().block_timestamp(),
+                period: DEFAULT_PERIOD,
+                epoch_offset: 0,
+
+                keeper_reward_fixed: 0,
+                keeper_reward_rate: 0,
+
+                dao_fund: None,
+
+                a_usd: None,
+                buybacks: HashMap::new(),
+                collaterals: HashMap::new(),
+
+                auctions: HashMap::new(),
+                auction_rounds: HashMap::new(),
+                auction_bids: HashMap::new(),
+
+                bond_limits: HashMap::new(),
+                bond_allowlist: HashMap::new(),
+                bond_purchases: HashMap::new(),
+
+                epoch_records: HashMap::new(),
+
+                pools: HashMap::new(),
+                pool_count: 0,
+
+                share_sale: Default::default(),
+
+                util: Lazy::new(util),
+                share: Lazy::new(share),
+                guard: Guard::new(),
+            };
+            instance._add_pool(cash_address, bond_address, oracle_address, boardroom_address);
+            instance
+        }
+
+        fn _only_operator(&self) {
+            let sender = Self::env().caller();
+            assert!(self.operator == sender, "Treasury: caller is not the operator");
+        }
+
+        fn _when_not_paused(&self, operation: Operation) {
+            assert!(!self.is_paused(operation), "Treasury: operation is paused");
+        }
+
+        fn _not_migrated(&self) {
+            assert!(!self.migrated, "Treasury: migrated");
+        }
+
+        fn _add_pool(&mut self,
+                     cash_address: AccountId,
+                     bond_address: AccountId,
+                     oracle_address: AccountId,
+                     boardroom_address: AccountId) -> u32 {
+            let pool_id: u32 = self.pool_count;
+            let pool = Pool {
+                cash: cash_address,
+                bond: bond_address,
+                oracle: oracle_address,
+                boardroom: boardroom_address,
+                target_feed: None,
+                ceiling_rate: DEFAULT_CEILING_RATE,
+                bond_cap: 0,
+                accumulated_seigniorage: 0,
+                next_allocation_epoch: 0,
+                keeper_rewards_paid: 0,
+                share_sale_trigger: Default::default(),
+                auction_bonds_owed: 0,
+            };
+            self.pools.insert(pool_id, pool);
+            self.pool_count = pool_id.checked_add(1).expect("failed at _add_pool the `treasury` contract");
+
+            self.env().emit_event(PoolAdded {
+                pool_id,
+                cash: cash_address,
+                bond: bond_address,
+                oracle: oracle_address,
+                boardroom: boardroom_address,
+            });
+            return pool_id;
+        }
+
+        fn _pool(&self, pool_id: u32) -> Pool {
+            return self.pools.get(&pool_id).expect("Treasury: pool does not exist").clone();
+        }
+
+        fn _cash(&self, pool: &Pool) -> Asset {
+            return FromAccountId::from_account_id(pool.cash);
+        }
+
+        fn _bond(&self, pool: &Pool) -> Asset {
+            return FromAccountId::from_account_id(pool.bond);
+        }
+
+        fn _oracle(&self, pool: &Pool) -> Oracle {
+            return FromAccountId::from_account_id(pool.oracle);
+        }
+
+        fn _boardroom(&self, pool: &Pool) -> Boardroom {
+            return FromAccountId::from_account_id(pool.boardroom);
+        }
+
+        fn _buyback(&self, pool_id: u32) -> Buyback {
+            return self.buybacks.get(&pool_id).cloned().unwrap_or_default();
+        }
+
+        fn _collateral(&self, pool_id: u32) -> Collateral {
+            return self.collaterals.get(&pool_id).cloned().unwrap_or_default();
+        }
+
+        fn _epoch_record(&self, pool_id: u32, epoch: u32) -> EpochRecord {
+            return self.epoch_records.get(&(pool_id, epoch)).cloned().unwrap_or_default();
+        }
+
+        fn _record_bonds_sold(&mut self, pool_id: u32, epoch: u32, amount: u128) {
+            let mut record = self._epoch_record(pool_id, epoch);
+            record.bonds_sold = record.bonds_sold.checked_add(amount).expect("failed at _record_bonds_sold the `treasury` contract");
+            self.epoch_records.insert((pool_id, epoch), record);
+        }
+
+        fn _bond_limit(&self, pool_id: u32) -> BondLimit {
+            return self.bond_limits.get(&pool_id).cloned().unwrap_or_default();
+        }
+
+        /// Cut the cash spent on the bonds to what the account can still spend in this epoch,
+        /// and check the allowlist in the allowlist phase.
+        fn _bond_purchase_allowance(&self, pool_id: u32, account: AccountId, amount: u128) -> u128 {
+            let limit = self._bond_limit(pool_id);
+            if limit.allowlist_duration > 0 {
+                let epoch_start: u64 = self.next_epoch_point().checked_sub(self.period).expect("failed at _bond_purchase_allowance the `treasury` contract");
+                let allowlist_end: u64 = epoch_start.checked_add(limit.allowlist_duration).expect("failed at _bond_purchase_allowance the `treasury` contract");
+                if Self::env().block_timestamp() < allowlist_end {
+                    assert!(self.is_bond_allowlisted(pool_id, account), "Treasury: caller is not allowlisted");
+                }
+            }
+
+            if limit.account_cap == 0 {
+                return amount;
+            }
+            let purchased: u128 = self.get_bond_purchased(pool_id, account);
+            let left: u128 = limit.account_cap.checked_sub(self.util.math_min(limit.account_cap, purchased)).expect("failed at _bond_purchase_allowance the `treasury` contract");
+            return self.util.math_min(amount, left);
+        }
+
+        fn _record_bond_purchase(&mut self, pool_id: u32, account: AccountId, amount: u128) {
+            let epoch: u32 = self.epoch();
+            let purchased: u128 = self.get_bond_purchased(pool_id, account).checked_add(amount).expect("failed at _record_bond_purchase the `treasury` contract");
+            self.bond_purchases.insert((pool_id, account), (epoch, purchased));
+        }
+
+        fn _auction(&self, pool_id: u32) -> Auction {
+            return self.auctions.get(&pool_id).cloned().unwrap_or_default();
+        }
+
+        /// Get the price of the round at the time `at`, it descends linearly and stops at the floor price.
+        fn _auction_price(&self, auction: &Auction, round: &AuctionRound, at: u64) -> u128 {
+            let one_unit_with_decimal = self.util.get_one_unit_with_decimal();
+            let mul_value = one_unit_with_decimal.checked_mul(auction.start_rate).expect("failed at _auction_price the `treasury` contract");
+            let start_price = mul_value.checked_div(BASIS_POINT).expect("failed at _auction_price the `treasury` contract");
+            let mul_value = one_unit_with_decimal.checked_mul(auction.floor_rate).expect("failed at _auction_price the `treasury` contract");
+            let floor_price = mul_value.checked_div(BASIS_POINT).expect("failed at _auction_price the `treasury` contract");
+
+            let at = if at > round.end_time { round.end_time } else { at };
+            let elapsed: u64 = at.checked_sub(round.start_time).expect("failed at _auction_price the `treasury` contract");
+            if auction.duration == 0 || elapsed >= auction.duration {
+                return floor_price;
+            }
+
+            let drop = start_price.checked_sub(floor_price).expect("failed at _auction_price the `treasury` contract");
+            let mul_value = drop.checked_mul(elapsed as u128).expect("failed at _auction_price the `treasury` contract");
+            let drop = mul_value.checked_div(auction.duration as u128).expect("failed at _auction_price the `treasury` contract");
+            return start_price.checked_sub(drop).expect("failed at _auction_price the `treasury` contract");
+        }
+
+        /// Get the uniform clearing price of the round. If the capacity is not filled, it is the price at the end
+        /// of the round, or the price at which the committed cash buys the whole capacity if it is higher.
+        fn _clearing_price(&self, auction: &Auction, round: &AuctionRound) -> u128 {
+            if round.clearing_price > 0 {
+                return round.clearing_price;
+            }
+            let end_price = self._auction_price(auction, round, round.end_time);
+            let one_unit_with_decimal = self.util.get_one_unit_with_decimal();
+            let mul_value = round.committed.checked_mul(one_unit_with_decimal).expect("failed at _clearing_price the `treasury` contract");
+            let demand_price = mul_value.checked_div(round.capacity).expect("failed at _clearing_price the `treasury` contract");
+            if demand_price > end_price {
+                return demand_price;
+            }
+            return end_price;
+        }
+
+        /// Get the bonds sold by the round at the clearing price.
+        fn _round_bonds(&self, round: &AuctionRound, clearing_price: u128) -> u128 {
+            let one_unit_with_decimal = self.util.get_one_unit_with_decimal();
+            let mul_value = round.committed.checked_mul(one_unit_with_decimal).expect("failed at _round_bonds the `treasury` contract");
+            return mul_value.checked_div(clearing_price).expect("failed at _round_bonds the `treasury` contract");
+        }
+
+        /// Count the bonds sold by the round as owed to the bidders once its clearing price is known.
+        fn _settle_auction_round(&mut self, pool_id: u32, pool: &mut Pool, epoch: u32) {
+            let mut round = match self.auction_rounds.get(&(pool_id, epoch)).cloned() {
+                Some(round) => round,
+                None => return,
+            };
+            let clearing_price: u128 = self.get_clearing_price(pool_id, epoch);
+            if round.settled || clearing_price == 0 {
+                return;
+            }
+            let bonds = self._round_bonds(&round, clearing_price);
+            pool.auction_bonds_owed = pool.auction_bonds_owed.checked_add(bonds).expect("failed at _settle_auction_round the `treasury` contract");
+            round.settled = true;
+            self.auction_rounds.insert((pool_id, epoch), round);
+        }
+
+        /// Get the bonds sold by the auction which are not claimed, so not minted yet.
+        /// The last round is counted at its capacity until its clearing price is known.
+        fn _auction_bonds_owed(&self, pool_id: u32, pool: &Pool) -> u128 {
+            let mut owed: u128 = pool.auction_bonds_owed;
+            if let Some(last_epoch) = self._auction(pool_id).last_epoch {
+                if let Some(round) = self.auction_rounds.get(&(pool_id, last_epoch)) {
+                    if !round.settled {
+                        let clearing_price: u128 = self.get_clearing_price(pool_id, last_epoch);
+                        let bonds: u128 = if clearing_price > 0 { self._round_bonds(round, clearing_price) } else { round.capacity };
+                        owed = owed.checked_add(bonds).expect("failed at _auction_bonds_owed the `treasury` contract");
+                    }
+                }
+            }
+            return owed;
+        }
+
+        /// Open the round of the current epoch: the capacity is the bond cap, less the bonds owed to the bidders.
+        fn _open_auction_round(&mut self, pool_id: u32, pool: &mut Pool, auction: &mut Auction, epoch: u32, cash_price: u128) -> AuctionRound {
+            // the last round has ended, its bonds are owed and counted against the bond cap.
+            if let Some(last_epoch) = auction.last_epoch {
+                self._settle_auction_round(pool_id, pool, last_epoch);
+            }
+
+            self._update_conversion_limit(pool_id, pool, cash_price);
+            let capacity = pool.bond_cap;
+            assert!(capacity > 0, "Treasury: amount exceeds bond cap");
+
+            let round = AuctionRound {
+                capacity,
+                start_time: Self::env().block_timestamp(),
+                end_time: self.next_epoch_point(),
+                committed: 0,
+                clearing_price: 0,
+                settled: false,
+            };
+            auction.last_epoch = Some(epoch);
+
+            self.env().emit_event(AuctionOpened {
+                pool_id,
+                epoch,
+                capacity,
+            });
+            return round;
+        }
+
+        /// The epochs passed since `start_time`.
+        fn _epochs_passed(&self) -> u64 {
+            let now: u64 = Self::env().block_timestamp();
+            let elapsed: u64 = now.checked_sub(self.start_time).expect("failed at _epochs_passed the `treasury` contract");
+            return elapsed.checked_div(self.period).expect("failed at _epochs_passed the `treasury` contract");
+        }
+
+        fn _keeper_reward(&self, seigniorage: u128) -> u128 {
+            let slice: u128 = seigniorage.checked_mul(self.keeper_reward_rate).expect("failed at _keeper_reward the `treasury` contract");
+            let slice: u128 = slice.checked_div(BASIS_POINT).expect("failed at _keeper_reward the `treasury` contract");
+            let reward: u128 = self.keeper_reward_fixed.checked_add(slice).expect("failed at _keeper_reward the `treasury` contract");
+            return self.util.math_min(reward, seigniorage);
+        }
+
+        fn _a_usd(&self) -> TokenStub {
+            let a_usd_address = self.a_usd.expect("Treasury: aUSD is not set");
+            return FromAccountId::from_account_id(a_usd_address);
+        }
+
+        /// Split the ALC amount into the aUSD part and the ALS part with the collateral ratio.
+        /// The ALC is valued at the target price. Minting rounds both parts up and redeeming rounds them down,
+        /// so the cash is never minted for less than its value.
+        fn _split_fractional(&self, pool_id: u32, ratio: u128, amount: u128, round_up: bool) -> (u128, u128) {
+            let one_unit_with_decimal = self.util.get_one_unit_with_decimal();
+            let value = self._mul_div(amount, self.get_target_price(pool_id), one_unit_with_decimal, round_up);
+
+            let collateral = self._mul_div(value, ratio, BASIS_POINT, round_up);
+            // the share part takes the rest of the value, counted with the collateral part rounded down.
+            let collateral_down = self._mul_div(value, ratio, BASIS_POINT, false);
+            let share_value = value.checked_sub(collateral_down).expect("failed at _split_fractional the `treasury` contract");
+            let pool = self._pool(pool_id);
+            let share_price: u128 = self._oracle(&pool).get_share_price();
+            assert!(share_price > 0, "Treasury: share price is not set");
+            let share = self._mul_div(share_value, one_unit_with_decimal, share_price, round_up);
+            return (collateral, share);
+        }
+
+        /// Get `a * b / c`, rounded up if `round_up` is true.
+        fn _mul_div(&self, a: u128, b: u128, c: u128, round_up: bool) -> u128 {
+            let mul_value = a.checked_mul(b).expect("failed at _mul_div the `treasury` contract");
+            let mut ret = mul_value.checked_div(c).expect("failed at _mul_div the `treasury` contract");
+            if round_up && mul_value % c > 0 {
+                ret = ret.checked_add(1).expect("failed at _mul_div the `treasury` contract");
+            }
+            return ret;
+        }
+
+        /// Get the price relative to the target price: `target_price * rate / 10000`.
+        fn _relative_price(&self, pool_id: u32, rate: u128) -> u128 {
+            let mul_value = self.get_target_price(pool_id).checked_mul(rate).expect("failed at _relative_price the `treasury` contract");
+            return mul_value.checked_div(BASIS_POINT).expect("failed at _relative_price the `treasury` contract");
+        }
+
+        fn _cash_balance_of_this(&self, pool: &Pool) -> u128 {
+            let this = self.env().account_id();
+            let b: u128 = self._cash(pool).balance_of(this);
+            return b;
+        }
+
+        fn _circulating_supply(&self, pool: &Pool) -> u128 {
+            let cash_supply: u128 = self._cash(pool).total_supply();
+            let r = cash_supply.checked_sub(pool.accumulated_seigniorage).expect("failed at _circulating_supply the `treasury` contract");
+            return r;
+        }
+
+        fn _update_conversion_limit(&self, pool_id: u32, pool: &mut Pool, cash_price: u128) {
+            let target_price = self.get_target_price(pool_id);
+            let percentage = target_price.checked_sub(cash_price).expect("failed at _update_conversion_limit the `treasury` contract");
+
+            let cap = self._circulating_supply(pool).checked_mul(percentage).expect("failed at _update_conversion_limit the `treasury` contract");
+
+            let b_cap = cap.checked_div(target_price).expect("failed at _update_conversion_limit the `treasury` contract");
+
+            // the auction bonds not claimed yet will be minted.
+            let bond_supply: u128 = self._bond(pool).total_supply().checked_add(self._auction_bonds_owed(pool_id, pool)).expect("failed at _update_conversion_limit the `treasury` contract");
+
+            pool.bond_cap = b_cap.checked_sub(self.util.math_min(b_cap, bond_supply)).expect("failed at _update_conversion_limit the `treasury` contract");
+        }
+
+        fn _check_operator(&self, pool: &Pool) {
+            let this = self.env().account_id();
+            assert!(self._cash(pool).operator() == this &&
+                    self._bond(pool).operator() == this &&
+                    self.share.operator() == this &&
+                    self._boardroom(pool).operator() == this, "Treasury: need more permission");
+        }
+
+        fn _is_protocol_token(&self, token: AccountId) -> bool {
+            if token == self.share.to_account_id() || Some(token) == self.a_usd {
+                return true;
+            }
+            return self.pools.values().any(|pool| pool.cash == token || pool.bond == token);
+        }
+
+        /// The guarded messages are scoped by the pool, a keeper can act on every pool in the same block.
+        fn _guard_enter(&mut self, pool_id: u32) {
+            let block_num:u32 = Self::env().block_number();
+            let sender = Self::env().caller();
+            self.guard.enter_scope(sender, pool_id, block_num);
+        }
+
+        fn _guard_exit(&mut self) {
+            self.guard.exit();
+        }
+
+        /// Get the operator who can operate this contract.
+        #[ink(message)]
+        pub fn operator(&self) -> AccountId {
+            return self.operator;
+        }
+
+        /// Switch the operator of this contract.
+        #[ink(message)]
+        pub fn transfer_operator(&mut self, new_operator:AccountId)  {
+            self._only_operator();
+            self.operator = new_operator;
+        }
+
+        /// Get the last block the account acted in the pool.
+        #[ink(message)]
+        pub fn last_action_block(&self, account: AccountId, pool_id: u32) -> Option<u32> {
+            return self.guard.last_scope_action(&account, pool_id);
+        }
+
+        /// Add a new pegged cash token with its (cash, bond, oracle, boardroom) set, return the pool id.
+        #[ink(message)]
+        pub fn add_pool(&mut self,
+                        cash_address: AccountId,
+                        bond_address: AccountId,
+                        oracle_address: AccountId,
+                        boardroom_address: AccountId) -> u32 {
+            self._only_operator();
+            self._not_migrated();
+            return self._add_pool(cash_address, bond_address, oracle_address, boardroom_address);
+        }
+
+        /// Get the pool by id.
+        #[ink(message)]
+        pub fn get_pool(&self, pool_id: u32) -> Option<Pool> {
+            return self.pools.get(&pool_id).cloned();
+        }
+
+        /// Get the count of the pools, the pool ids are `0..pool_count`.
+        #[ink(message)]
+        pub fn pool_count(&self) -> u32 {
+            return self.pool_count;
+        }
+
+        /// Get the guardian who can pause the operations.
+        #[ink(message)]
+        pub fn guardian(&self) -> AccountId {
+            return self.guardian;
+        }
+
+        /// Switch the guardian. Only the operator can do it.
+        #[ink(message)]
+        pub fn set_guardian(&mut self, new_guardian: AccountId) {
+            self._only_operator();
+            self.guardian = new_guardian;
+            self.env().emit_event(GuardianChanged {
+                guardian: new_guardian,
+            });
+        }
+
+        /// Whether the operation is paused.
+        #[ink(message)]
+        pub fn is_paused(&self, operation: Operation) -> bool {
+            return self.paused.get(&operation).copied().unwrap_or(false);
+        }
+
+        /// Pause the operation. Called by the guardian or the operator.
+        #[ink(message)]
+        pub fn pause(&mut self, operation: Operation) {
+            let sender = Self::env().caller();
+            assert!(sender == self.guardian || sender == self.operator, "Treasury: caller is not the guardian");
+            self.paused.insert(operation, true);
+            self.env().emit_event(Paused {
+                operation,
+                by: sender,
+            });
+        }
+
+        /// Unpause the operation. Only the operator can do it.
+        #[ink(message)]
+        pub fn unpause(&mut self, operation: Operation) {
+            self._only_operator();
+            self.paused.take(&operation);
+            self.env().emit_event(Unpaused {
+                operation,
+                by: Self::env().caller(),
+            });
+        }
+
+        /// Unpause the operation of the pool's boardroom. The treasury is the operator of the boardroom,
+        /// so the unpausing goes through here.
+        #[ink(message)]
+        pub fn unpause_boardroom(&mut self, pool_id: u32, operation: BoardroomOperation) {
+            self._only_operator();
+            let pool = self._pool(pool_id);
+            self._boardroom(&pool).unpause(operation);
+        }
+
+        /// Switch the guardian of the pool's boardroom.
+        #[ink(message)]
+        pub fn set_boardroom_guardian(&mut self, pool_id: u32, new_guardian: AccountId) {
+            self._only_operator();
+            let pool = self._pool(pool_id);
+            self._boardroom(&pool).set_guardian(new_guardian);
+        }
+
+        /// Transfer the token sent to this contract by mistake to `to`. The ALC, ALB of any pool, ALS and aUSD can't be recovered.
+        #[ink(message)]
+        pub fn recover_unsupported(&mut self, token: AccountId, amount: u128, to: AccountId) {
+            self._only_operator();
+            assert!(!self._is_protocol_token(token), "Treasury: protocol token can't be recovered");
+            let mut unsupported: TokenStub = FromAccountId::from_account_id(token);
+            let ret: bool = unsupported.transfer(to, amount).is_ok();
+            assert!(ret, "Treasury: recover_unsupported transfer err");
+
+            self.env().emit_event(UnsupportedRecovered {
+                token,
+                to,
+                amount,
+            });
+        }
+
+        /// Set the withdraw and the reward lockups of the pool's boardroom in epochs, an epoch is the period of the treasury.
+        #[ink(message)]
+        pub fn set_boardroom_lockup(&mut self, pool_id: u32, withdraw_lockup_epochs: u32, reward_lockup_epochs: u32) {
+            self._only_operator();
+            let pool = self._pool(pool_id);
+            let period: u64 = self.period;
+            self._boardroom(&pool).set_lockup(withdraw_lockup_epochs, reward_lockup_epochs, period);
+        }
+
+        /// Set the time(ms) the reward of the pool's boardroom is streamed over.
+        #[ink(message)]
+        pub fn set_boardroom_reward_duration(&mut self, pool_id: u32, duration: u64) {
+            self._only_operator();
+            let pool = self._pool(pool_id);
+            self._boardroom(&pool).set_reward_duration(duration);
+        }
+
+        /// Add an extra reward token to the pool's boardroom, which can be allocated by `distributor`.
+        #[ink(message)]
+        pub fn add_boardroom_reward_token(&mut self, pool_id: u32, token: AccountId, distributor: AccountId) {
+            self._only_operator();
+            let pool = self._pool(pool_id);
+            self._boardroom(&pool).add_reward_token(token, distributor);
+        }
+
+        /// Switch the distributor of the extra reward token of the pool's boardroom.
+        #[ink(message)]
+        pub fn set_boardroom_reward_distributor(&mut self, pool_id: u32, token: AccountId, distributor: AccountId) {
+            self._only_operator();
+            let pool = self._pool(pool_id);
+            self._boardroom(&pool).set_reward_distributor(token, distributor);
+        }
+
+        /// Set the vote-escrowed ALS of the pool's boardroom.
+        #[ink(message)]
+        pub fn set_boardroom_lock_mode(&mut self, pool_id: u32, enabled: bool, max_duration: u64, max_boost: u128) {
+            self._only_operator();
+            let pool = self._pool(pool_id);
+            self._boardroom(&pool).set_lock_mode(enabled, max_duration, max_boost);
+        }
+
+        /// Transfer the token sent to the pool's boardroom by mistake to `to`.
+        #[ink(message)]
+        pub fn recover_boardroom_unsupported(&mut self, pool_id: u32, token: AccountId, amount: u128, to: AccountId) {
+            self._only_operator();
+            let pool = self._pool(pool_id);
+            self._boardroom(&pool).recover_unsupported(token, amount, to);
+        }
+
+        /// Transfer the rounding dust of the reward token of the pool's boardroom to `to`.
+        #[ink(message)]
+        pub fn sweep_boardroom_dust(&mut self, pool_id: u32, token: AccountId, to: AccountId) {
+            self._only_operator();
+            let pool = self._pool(pool_id);
+            self._boardroom(&pool).sweep_dust(token, to);
+        }
+
+        /// Unpause the operation of the asset(ALC, ALB or ALS) whose operator is the treasury.
+        #[ink(message)]
+        pub fn unpause_asset(&mut self, asset_address: AccountId, operation: AssetOperation) {
+            self._only_operator();
+            let mut asset: Asset = FromAccountId::from_account_id(asset_address);
+            asset.unpause(operation);
+        }
+
+        /// Switch the guardian of the asset(ALC, ALB or ALS) whose operator is the treasury.
+        #[ink(message)]
+        pub fn set_asset_guardian(&mut self, asset_address: AccountId, new_guardian: AccountId) {
+            self._only_operator();
+            let mut asset: Asset = FromAccountId::from_account_id(asset_address);
+            asset.set_guardian(new_guardian);
+        }
+
+        /// Get the ALC of the pool which is reserved for the bond redemption.
+        #[ink(message)]
+        pub fn accumulated_seigniorage(&self, pool_id: u32) -> u128 {
+            return self._pool(pool_id).accumulated_seigniorage;
+        }
+
+        /// Get the record of the pool in the epoch.
+        #[ink(message)]
+        pub fn get_epoch_record(&self, pool_id: u32, epoch: u32) -> Option<EpochRecord> {
+            return self.epoch_records.get(&(pool_id, epoch)).cloned();
+        }
+
+        /// Get the records of the pool from `from_epoch`, no more than `limit`(at most 100) epochs are scanned.
+        /// The epochs without record are skipped.
+        #[ink(message)]
+        pub fn get_epoch_records(&self, pool_id: u32, from_epoch: u32, limit: u32) -> Vec<(u32, EpochRecord)> {
+            let mut records: Vec<(u32, EpochRecord)> = Vec::new();
+            let limit: u32 = if limit > MAX_PAGE_SIZE { MAX_PAGE_SIZE } else { limit };
+            let current: u32 = self.epoch();
+            for epoch in from_epoch..from_epoch.saturating_add(limit) {
+                if epoch > current {
+                    break;
+                }
+                if let Some(record) = self.epoch_records.get(&(pool_id, epoch)) {
+                    records.push((epoch, record.clone()));
+                }
+            }
+            return records;
+        }
+
+        /// Whether this treasury is migrated to a new one and disabled.
+        #[ink(message)]
+        pub fn migrated(&self) -> bool {
+            return self.migrated;
+        }
+
+        /// Get the queued migration target and the time(ms) after which it can be executed.
+        #[ink(message)]
+        pub fn get_migration(&self) -> (Option<AccountId>, u64) {
+            return (self.migration_target, self.migration_eta);
+        }
+
+        /// Queue the migration to the new treasury, it can be executed after the timelock.
+        #[ink(message)]
+        pub fn queue_migration(&mut self, new_treasury: AccountId) {
+            self._only_operator();
+            self._not_migrated();
+            let this = self.env().account_id();
+            assert!(new_treasury != this && new_treasury != AccountId::from([0; 32]), "Treasury: invalid migration target");
+
+            let eta = Self::env().block_timestamp().checked_add(MIGRATION_DELAY).expect("failed at queue_migration the `treasury` contract");
+            self.migration_target = Some(new_treasury);
+            self.migration_eta = eta;
+
+            self.env().emit_event(MigrationQueued {
+                target: new_treasury,
+                eta,
+            });
+        }
+
+        /// Cancel the queued migration.
+        #[ink(message)]
+        pub fn cancel_migration(&mut self) {
+            self._only_operator();
+            let target = self.migration_target.take().expect("Treasury: no migration queued");
+            self.migration_eta = 0;
+
+            self.env().emit_event(MigrationCancelled {
+                target,
+            });
+        }
+
+        /// Migrate to the new treasury after the timelock. For every pool, transfer the ALC/ALB/aUSD reserve and the
+        /// operator of ALC, ALB and boardroom to the new treasury, then hand over ALS and disable this one forever.
+        /// The bonds owed to the auction bidders are kept here to be claimed, the migration waits for an open round.
+        /// The accumulated seigniorage in the `Migrated` events is imported by `import_seigniorage` of the new treasury.
+        #[ink(message)]
+        pub fn migrate(&mut self, new_treasury: AccountId) {
+            self._only_operator();
+            self._not_migrated();
+            assert!(self.migration_target == Some(new_treasury), "Treasury: migration target not queued");
+            assert!(Self::env().block_timestamp() >= self.migration_eta, "Treasury: migration is timelocked");
+
+            let this = self.env().account_id();
+            for pool_id in 0..self.pool_count {
+                let mut pool = self._pool(pool_id);
+
+                // Mint the bonds owed to the bidders to this treasury, they are claimed from it after the migration.
+                // A round whose clearing price is not known yet can't be carried over.
+                if let Some(last_epoch) = self._auction(pool_id).last_epoch {
+                    self._settle_auction_round(pool_id, &mut pool, last_epoch);
+                    let round = self.auction_rounds.get(&(pool_id, last_epoch)).cloned().unwrap_or_default();
+                    assert!(round.settled || round.committed == 0, "Treasury: auction round is open");
+                    self.pools.insert(pool_id, pool.clone());
+                }
+                let bonds_owed: u128 = pool.auction_bonds_owed;
+                if bonds_owed > 0 {
+                    let ret: bool = self._bond(&pool).mint(this, bonds_owed).is_ok();
+                    assert!(ret, "Treasury: migrate bond.mint err");
+                }
+
+                // Transfer the reserve.
+                let mut cash = self._cash(&pool);
+                let cash_reserve: u128 = cash.balance_of(this);
+                if cash_reserve > 0 {
+                    let ret: bool = cash.transfer(new_treasury, cash_reserve).is_ok();
+                    assert!(ret, "Treasury: migrate cash.transfer err");
+                }
+
+                let mut bond = self._bond(&pool);
+                let bond_reserve: u128 = bond.balance_of(this).checked_sub(bonds_owed).expect("failed at migrate the `treasury` contract");
+                if bond_reserve > 0 {
+                    let ret: bool = bond.transfer(new_treasury, bond_reserve).is_ok();
+                    assert!(ret, "Treasury: migrate bond.transfer err");
+                }
+
+                let mut buyback = self._buyback(pool_id);
+                let mut collateral = self._collateral(pool_id);
+                let a_usd_reserve: u128 = buyback.reserve.checked_add(collateral.reserve).expect("failed at migrate the `treasury` contract");
+                if a_usd_reserve > 0 {
+                    buyback.reserve = 0;
+                    collateral.reserve = 0;
+                    self.buybacks.insert(pool_id, buyback);
+                    self.collaterals.insert(pool_id, collateral);
+                    let ret: bool = self._a_usd().transfer(new_treasury, a_usd_reserve).is_ok();
+                    assert!(ret, "Treasury: migrate a_usd.transfer err");
+                }
+
+                // Hand over the operator.
+                cash.transfer_operator(new_treasury);
+                bond.transfer_operator(new_treasury);
+                self._boardroom(&pool).transfer_operator(new_treasury);
+
+                self.env().emit_event(Migrated {
+                    pool_id,
+                    target: new_treasury,
+                    cash_reserve,
+                    bond_reserve,
+                    a_usd_reserve,
+                    accumulated_seigniorage: pool.accumulated_seigniorage,
+                });
+            }
+            self.share.transfer_operator(new_treasury);
+
+            self.migrated = true;
+            self.migration_target = None;
+            self.migration_eta = 0;
+        }
+
+        /// Import the pool's accumulated seigniorage of the old treasury after the migration, once for each pool.
+        /// The reserve of the old treasury is transferred first, the accumulated seigniorage is not more than the cash of this treasury.
+        #[ink(message)]
+        pub fn import_seigniorage(&mut self, pool_id: u32, amount: u128) {
+            self._only_operator();
+            self._not_migrated();
+            let imported: bool = self.seigniorage_imported.get(&pool_id).copied().unwrap_or(false);
+            assert!(!imported, "Treasury: seigniorage is imported");
+            let mut pool = self._pool(pool_id);
+            let accumulated: u128 = pool.accumulated_seigniorage.checked_add(amount).expect("failed at import_seigniorage the `treasury` contract");
+            assert!(accumulated <= self._cash_balance_of_this(&pool), "Treasury: seigniorage is more than the reserve");
+            pool.accumulated_seigniorage = accumulated;
+            self.pools.insert(pool_id, pool);
+            self.seigniorage_imported.insert(pool_id, true);
+
+            self.env().emit_event(SeigniorageImported {
+                pool_id,
+                amount,
+            });
+        }
+
+        /// Get the current epoch, it advances every `period` whether the seigniorage is allocated or not.
+        #[ink(message)]
+        pub fn epoch(&self) -> u32 {
+            let passed = self._epochs_passed() as u32;
+            return self.epoch_offset.checked_add(passed).expect("failed at epoch the `treasury` contract");
+        }
+
+        /// Get the length(ms) of an epoch.
+        #[ink(message)]
+        pub fn get_period(&self) -> u64 {
+            return self.period;
+        }
+
+        /// Get the time(ms) when the next epoch begins.
+        #[ink(message)]
+        pub fn next_epoch_point(&self) -> u64 {
+            let passed: u64 = self._epochs_passed().checked_add(1).expect("failed at next_epoch_point the `treasury` contract");
+            let elapsed: u64 = self.period.checked_mul(passed).expect("failed at next_epoch_point the `treasury` contract");
+            return self.start_time.checked_add(elapsed).expect("failed at next_epoch_point the `treasury` contract");
+        }
+
+        /// Set the length(ms) of an epoch, it takes effect from the current epoch.
+        /// It should be longer than the allowlist phase of every pool.
+        #[ink(message)]
+        pub fn set_period(&mut self, period: u64) {
+            self._only_operator();
+            assert!(period > 0, "Treasury: period should above 0");
+            // the allowlist phase of the bond purchase is shorter than an epoch, as checked by set_bond_limit.
+            assert!(self.bond_limits.values().all(|limit| limit.allowlist_duration < period), "Treasury: allowlist phase is too long");
+
+            // rebase the epoch on the begin of the current epoch.
+            let epoch: u32 = self.epoch();
+            let elapsed: u64 = self.period.checked_mul(self._epochs_passed()).expect("failed at set_period the `treasury` contract");
+            self.start_time = self.start_time.checked_add(elapsed).expect("failed at set_period the `treasury` contract");
+            self.epoch_offset = epoch;
+            self.period = period;
+
+            // keep the lockups of the boardrooms in the epochs of the treasury.
+            for pool_id in 0..self.pool_count {
+                let pool = self._pool(pool_id);
+                let (withdraw_lockup_epochs, reward_lockup_epochs, _) = self._boardroom(&pool).get_lockup();
+                if withdraw_lockup_epochs > 0 {
+                    self._boardroom(&pool).set_lockup(withdraw_lockup_epochs, reward_lockup_epochs, period);
+                }
+            }
+        }
+
+        /// Get the keeper reward: the fixed ALC amount and the basis point of the seigniorage.
+        #[ink(message)]
+        pub fn get_keeper_reward(&self) -> (u128, u128) {
+            return (self.keeper_reward_fixed, self.keeper_reward_rate);
+        }
+
+        /// Get the total cash of the pool paid to the keepers.
+        #[ink(message)]
+        pub fn keeper_rewards_paid(&self, pool_id: u32) -> u128 {
+            return self._pool(pool_id).keeper_rewards_paid;
+        }
+
+        /// Set the keeper reward paid to whoever calls allocate_seigniorage successfully.
+        /// The reward is `fixed + seigniorage * rate / 10000`, and never more than the seigniorage.
+        #[ink(message)]
+        pub fn set_keeper_reward(&mut self, fixed: u128, rate: u128) {
+            self._only_operator();
+            assert!(rate <= MAX_KEEPER_REWARD_RATE, "Treasury: keeper reward rate is too high");
+            self.keeper_reward_fixed = fixed;
+            self.keeper_reward_rate = rate;
+        }
+
+        /// Get the DAO fund which receives the boardroom's share when no ALS is staked.
+        #[ink(message)]
+        pub fn dao_fund(&self) -> Option<AccountId> {
+            return self.dao_fund;
+        }
+
+        /// Set the DAO fund. When it is None, the boardroom's share is kept as the treasury reserve
+        /// if no ALS is staked.
+        #[ink(message)]
+        pub fn set_dao_fund(&mut self, fund: Option<AccountId>) {
+            self._only_operator();
+            self.dao_fund = fund;
+        }
+
+        /// Get the oracle which feeds the target price of the pool. When it is None, the target price is $1.
+        #[ink(message)]
+        pub fn target_feed(&self, pool_id: u32) -> Option<AccountId> {
+            return self._pool(pool_id).target_feed;
+        }
+
+        /// Set the oracle which feeds the target price of the pool, e.g. a CPI or EUR index.
+        #[ink(message)]
+        pub fn set_target_feed(&mut self, pool_id: u32, feed: Option<AccountId>) {
+            self._only_operator();
+            let mut pool = self._pool(pool_id);
+            pool.target_feed = feed;
+            self.pools.insert(pool_id, pool);
+        }
 
-        room_address: AccountId,
-        util:  Lazy<Util>,
-        oracle:  Lazy<Oracle>,
-        cash: Lazy<Asset>,
-        bond: Lazy<Asset>,
-        share: Lazy<Asset>,
-        boardroom:  Lazy<Boardroom>,
+        /// Get the target price which the cash of the pool is pegged to.
+        #[ink(message)]
+        pub fn get_target_price(&self, pool_id: u32) -> u128 {
+            match self._pool(pool_id).target_feed {
+                Some(feed) => {
+                    let oracle: Oracle = FromAccountId::from_account_id(feed);
+                    let target_price: u128 = oracle.get_target_price();
+                    assert!(target_price > 0, "Treasury: target price is not set");
+                    target_price
+                }
+                None => self.util.get_one_unit_with_decimal(),
+            }
+        }
 
-        status: HashMap<(u32, AccountId), bool>,
-    }
+        /// Get the ceiling price above which the bonds can be redeemed and the seigniorage is allocated.
+        #[ink(message)]
+        pub fn get_ceiling_price(&self, pool_id: u32) -> u128 {
+            return self._relative_price(pool_id, self._pool(pool_id).ceiling_rate);
+        }
 
-    /// Event emitted when an redeem_bonds occurs that user redeem the ALB.
-    #[ink(event)]
-    pub struct RedeemedBonds {
-        #[ink(topic)]
-        from: Option<AccountId>,
-        #[ink(topic)]
-        amount: u128,
-    }
+        /// Set the ceiling price relative to the target price, in basis point.
+        #[ink(message)]
+        pub fn set_ceiling_rate(&mut self, pool_id: u32, rate: u128) {
+            self._only_operator();
+            assert!(rate >= BASIS_POINT, "Treasury: ceiling should not below the target");
+            let mut pool = self._pool(pool_id);
+            pool.ceiling_rate = rate;
+            self.pools.insert(pool_id, pool);
+        }
 
-    /// Event emitted when an buy_bonds occurs that user buy the ALB.
-    #[ink(event)]
-    pub struct BoughtBonds {
-        #[ink(topic)]
-        from: Option<AccountId>,
-        #[ink(topic)]
-        amount: u128,
-    }
+        /// Get the aUSD which backs the buyback.
+        #[ink(message)]
+        pub fn a_usd(&self) -> Option<AccountId> {
+            return self.a_usd;
+        }
 
-    /// Event emitted when an allocate_seigniorage occurs that system allocate the reward.
-    #[ink(event)]
-    pub struct TreasuryFunded {
-        #[ink(topic)]
-        timestamp: u64,
-        #[ink(topic)]
-        seigniorage: u128,
-    }
+        /// Set the aUSD, it can't be switched while there is aUSD in the reserve.
+        #[ink(message)]
+        pub fn set_a_usd(&mut self, a_usd_address: AccountId) {
+            self._only_operator();
+            assert!(self.buybacks.values().all(|b| b.reserve == 0), "Treasury: buyback reserve is not empty");
+            assert!(self.collaterals.values().all(|c| c.reserve == 0), "Treasury: collateral reserve is not empty");
+            self.a_usd = Some(a_usd_address);
+        }
 
-    /// Event emitted when an allocate_seigniorage occurs that system allocate the reward.
-    #[ink(event)]
-    pub struct BoardroomFunded {
-        #[ink(topic)]
-        timestamp: u64,
-        #[ink(topic)]
-        seigniorage: u128,
-    }
+        /// Get the config and the state of the buyback of the pool.
+        #[ink(message)]
+        pub fn get_buyback(&self, pool_id: u32) -> Buyback {
+            return self._buyback(pool_id);
+        }
 
+        /// Set the buyback of the pool: the floor price relative to the target price in basis point below which the buyback opens,
+        /// the aUSD paid per cash and the max cash bought back per epoch.
+        #[ink(message)]
+        pub fn set_buyback(&mut self, pool_id: u32, floor_rate: u128, price: u128, epoch_cap: u128) {
+            self._only_operator();
+            self._pool(pool_id);
+            assert!(floor_rate <= BASIS_POINT, "Treasury: floor should not above the target");
+            let mut buyback = self._buyback(pool_id);
+            buyback.floor_rate = floor_rate;
+            buyback.price = price;
+            buyback.epoch_cap = epoch_cap;
+            self.buybacks.insert(pool_id, buyback);
+        }
 
-    impl Treasury {
-        /// Create new treasury
-        #[ink(constructor)]
-        pub fn new(util_address:AccountId,
-                   cash_address:AccountId,
-                   bond_address: AccountId,
-                   share_address: AccountId,
-                   oracle_address: AccountId,
-                   boardroom_address: AccountId) -> Self {
+        /// Transfer the aUSD from the caller to the buyback reserve of the pool.
+        #[ink(message)]
+        pub fn fund_buyback_reserve(&mut self, pool_id: u32, amount: u128) {
+            self._not_migrated();
+            self._pool(pool_id);
+            assert!(amount > 0, "Treasury: cannot fund with zero amount");
 
-            let util: Util = FromAccountId::from_account_id(util_address);
-            let cash: Asset = FromAccountId::from_account_id(cash_address);
-            let bond: Asset = FromAccountId::from_account_id(bond_address);
-            let share: Asset = FromAccountId::from_account_id(share_address);
-            let oracle: Oracle = FromAccountId::from_account_id(oracle_address);
-            let boardroom: Boardroom = FromAccountId::from_account_id(boardroom_address);
+            let sender = Self::env().caller();
+            let this = self.env().account_id();
+            let ret: bool = self._a_usd().transfer_from(sender, this, amount).is_ok();
+            assert!(ret, "Treasury: fund_buyback_reserve transfer_from err");
 
-            let instance = Self {
-                bond_cap: 0,
-                accumulated_seigniorage: 0,
+            let mut buyback = self._buyback(pool_id);
+            buyback.reserve = buyback.reserve.checked_add(amount).expect("failed at fund_buyback_reserve the `treasury` contract");
+            let reserve: u128 = buyback.reserve;
+            self.buybacks.insert(pool_id, buyback);
 
-                room_address: boardroom_address,
-                util: Lazy::new(util),
-                cash: Lazy::new(cash),
-                bond: Lazy::new(bond),
-                share: Lazy::new(share),
-                oracle: Lazy::new(oracle),
-                boardroom: Lazy::new(boardroom),
-                status: HashMap::new(),
-            };
-            instance
+            self.env().emit_event(BuybackReserveChanged {
+                pool_id,
+                by: sender,
+                reserve,
+            });
         }
 
-        fn _cash_balance_of_this(&self) -> u128 {
-            let this = self.env().account_id();
-            let b: u128 = self.cash.balance_of(this);
-            return b;
+        /// Withdraw the aUSD from the buyback reserve of the pool.
+        #[ink(message)]
+        pub fn withdraw_buyback_reserve(&mut self, pool_id: u32, amount: u128, to: AccountId) {
+            self._only_operator();
+            let mut buyback = self._buyback(pool_id);
+            assert!(amount <= buyback.reserve, "Treasury: withdraw request greater than the reserve");
+
+            buyback.reserve = buyback.reserve.checked_sub(amount).expect("failed at withdraw_buyback_reserve the `treasury` contract");
+            let reserve: u128 = buyback.reserve;
+            self.buybacks.insert(pool_id, buyback);
+
+            let ret: bool = self._a_usd().transfer(to, amount).is_ok();
+            assert!(ret, "Treasury: withdraw_buyback_reserve transfer err");
+
+            self.env().emit_event(BuybackReserveChanged {
+                pool_id,
+                by: Self::env().caller(),
+                reserve,
+            });
         }
 
-        fn _circulating_supply(&self) -> u128 {
-            let cash_supply: u128 = self.cash.total_supply();
-            let r = cash_supply.checked_sub(self.accumulated_seigniorage).expect("failed at _circulating_supply the `treasury` contract");
-            return r;
+        /// When the cash's price < floor price, user can sell the cash to the treasury at the buyback price,
+        /// the cash is burnt and the aUSD is paid from the reserve.
+        #[ink(message)]
+        pub fn buyback_cash(&mut self, pool_id: u32, amount: u128, min_a_usd_out: u128) {
+            self._when_not_paused(Operation::BuybackCash);
+            self._not_migrated();
+            let pool = self._pool(pool_id);
+            self._check_operator(&pool);
+            self._guard_enter(pool_id);
+            assert!(amount > 0, "Treasury: cannot sell zero amount");
+
+            let mut buyback = self._buyback(pool_id);
+            let cash_price: u128 = self._oracle(&pool).get_cash_price();
+            let floor_price: u128 = self._relative_price(pool_id, buyback.floor_rate);
+            assert!(cash_price < floor_price, "Treasury: cash_price not eligible for buyback");
+
+            // reset the cap when a new epoch begins.
+            let epoch: u32 = self.epoch();
+            if buyback.epoch != epoch {
+                buyback.epoch = epoch;
+                buyback.bought = 0;
+            }
+
+            let bought: u128 = buyback.bought.checked_add(amount).expect("failed at buyback_cash the `treasury` contract");
+            assert!(bought <= buyback.epoch_cap, "Treasury: amount exceeds buyback cap");
+
+            let one_unit_with_decimal = self.util.get_one_unit_with_decimal();
+            let mul_value = amount.checked_mul(buyback.price).expect("failed at buyback_cash the `treasury` contract");
+            let paid = mul_value.checked_div(one_unit_with_decimal).expect("failed at buyback_cash the `treasury` contract");
+            assert!(paid > 0 && paid >= min_a_usd_out, "Treasury: buyback price moved");
+            assert!(paid <= buyback.reserve, "Treasury: buyback reserve is not enough");
+
+            buyback.bought = bought;
+            buyback.reserve = buyback.reserve.checked_sub(paid).expect("failed at buyback_cash the `treasury` contract");
+            self.buybacks.insert(pool_id, buyback);
+
+            // Burn the user's cash.
+            let sender = Self::env().caller();
+            let burn_ret: bool = self._cash(&pool).burn_from(sender, amount).is_ok();
+            assert!(burn_ret, "Treasury: buyback_cash burn err");
+
+            // Pay the aUSD to user.
+            let ret: bool = self._a_usd().transfer(sender, paid).is_ok();
+            assert!(ret, "Treasury: buyback_cash transfer err");
+
+            self.env().emit_event(CashBoughtBack {
+                pool_id,
+                from: Some(sender),
+                amount,
+                paid,
+            });
+
+            self._guard_exit();
         }
 
-        fn _update_conversion_limit(&mut self, cash_price: u128) {
-            let cash_price_one = self.util.get_one_unit_with_decimal();
-            let percentage = cash_price_one.checked_sub(cash_price).expect("failed at _update_conversion_limit the `treasury` contract");
+        /// Get the config and the state of the partially collateralized cash of the pool.
+        #[ink(message)]
+        pub fn get_collateral(&self, pool_id: u32) -> Collateral {
+            return self._collateral(pool_id);
+        }
 
-            let cap = self._circulating_supply().checked_mul(percentage).expect("failed at _update_conversion_limit the `treasury` contract");
+        /// Set the partially collateralized mode of the pool: whether it is enabled, the collateral ratio and
+        /// the step of the ratio per epoch, both in basis point.
+        #[ink(message)]
+        pub fn set_collateral(&mut self, pool_id: u32, enabled: bool, ratio: u128, step: u128) {
+            self._only_operator();
+            self._pool(pool_id);
+            assert!(ratio <= BASIS_POINT && step <= BASIS_POINT, "Treasury: invalid collateral ratio");
+            let mut collateral = self._collateral(pool_id);
+            collateral.enabled = enabled;
+            collateral.ratio = ratio;
+            collateral.step = step;
+            self.collaterals.insert(pool_id, collateral);
+        }
 
-            let decimal = self.util.get_decimal();
-            let b_cap = cap.checked_div(decimal.into()).expect("failed at _update_conversion_limit the `treasury` contract");
+        /// Adjust the collateral ratio once per epoch. If the cash's price > the target price, the ratio is decreased,
+        /// if the cash's price < the target price, the ratio is increased.
+        #[ink(message)]
+        pub fn refresh_collateral_ratio(&mut self, pool_id: u32) {
+            self._not_migrated();
+            let pool = self._pool(pool_id);
+            let mut collateral = self._collateral(pool_id);
+            assert!(collateral.enabled, "Treasury: fractional mode is disabled");
+            let epoch: u32 = self.epoch();
+            assert!(epoch >= collateral.next_refresh_epoch, "Treasury: collateral ratio is refreshed");
 
-            let bond_supply: u128 = self.bond.total_supply();
+            let cash_price: u128 = self._oracle(&pool).get_cash_price();
+            let target_price: u128 = self.get_target_price(pool_id);
+            let step: u128 = collateral.step;
+            if cash_price > target_price {
+                collateral.ratio = collateral.ratio.checked_sub(self.util.math_min(step, collateral.ratio)).expect("failed at refresh_collateral_ratio the `treasury` contract");
+            } else if cash_price < target_price {
+                let ratio: u128 = collateral.ratio.checked_add(step).expect("failed at refresh_collateral_ratio the `treasury` contract");
+                collateral.ratio = self.util.math_min(ratio, BASIS_POINT);
+            }
+            collateral.next_refresh_epoch = epoch.checked_add(1).expect("failed at refresh_collateral_ratio the `treasury` contract");
+            let ratio: u128 = collateral.ratio;
+            self.collaterals.insert(pool_id, collateral);
 
-            self.bond_cap = b_cap.checked_sub(self.util.math_min(b_cap, bond_supply)).expect("failed at _update_conversion_limit the `treasury` contract");
+            self.env().emit_event(CollateralRatioUpdated {
+                pool_id,
+                epoch,
+                ratio,
+            });
         }
 
-        fn _check_operator(&self) {
+        /// Mint the cash by depositing the aUSD and burning the ALS with the collateral ratio.
+        /// The aUSD is valued at $1, the ALS is valued at the oracle's price.
+        #[ink(message)]
+        pub fn mint_fractional(&mut self, pool_id: u32, amount: u128, max_collateral_in: u128, max_share_in: u128) {
+            self._when_not_paused(Operation::MintFractional);
+            self._not_migrated();
+            let pool = self._pool(pool_id);
+            self._check_operator(&pool);
+            self._guard_enter(pool_id);
+            let mut collateral_state = self._collateral(pool_id);
+            assert!(collateral_state.enabled, "Treasury: fractional mode is disabled");
+            assert!(amount > 0, "Treasury: cannot mint zero amount");
+
+            let (collateral, share) = self._split_fractional(pool_id, collateral_state.ratio, amount, true);
+            assert!(collateral <= max_collateral_in && share <= max_share_in, "Treasury: collateral ratio moved");
+
+            let sender = Self::env().caller();
             let this = self.env().account_id();
-            assert!(self.cash.operator() == this &&
-                    self.bond.operator() == this &&
-                    self.share.operator() == this &&
-                    self.boardroom.operator() == this, "Treasury: need more permission");
+            if collateral > 0 {
+                let ret: bool = self._a_usd().transfer_from(sender, this, collateral).is_ok();
+                assert!(ret, "Treasury: mint_fractional transfer_from err");
+                collateral_state.reserve = collateral_state.reserve.checked_add(collateral).expect("failed at mint_fractional the `treasury` contract");
+                self.collaterals.insert(pool_id, collateral_state);
+            }
+
+            if share > 0 {
+                let ret: bool = self.share.burn_from(sender, share).is_ok();
+                assert!(ret, "Treasury: mint_fractional burn err");
+            }
+
+            let ret: bool = self._cash(&pool).mint(sender, amount).is_ok();
+            assert!(ret, "Treasury: mint_fractional mint err");
+
+            self.env().emit_event(MintedFractional {
+                pool_id,
+                from: Some(sender),
+                amount,
+                collateral,
+                share,
+            });
+
+            self._guard_exit();
         }
 
-        fn _check_same_sender_rented(&self) {
-            let block_num:u32 = Self::env().block_number();
+        /// Redeem the cash for the aUSD in the collateral reserve and the newly minted ALS with the collateral ratio.
+        #[ink(message)]
+        pub fn redeem_fractional(&mut self, pool_id: u32, amount: u128, min_collateral_out: u128, min_share_out: u128) {
+            self._when_not_paused(Operation::RedeemFractional);
+            self._not_migrated();
+            let pool = self._pool(pool_id);
+            self._check_operator(&pool);
+            self._guard_enter(pool_id);
+            let mut collateral_state = self._collateral(pool_id);
+            assert!(collateral_state.enabled, "Treasury: fractional mode is disabled");
+            assert!(amount > 0, "Treasury: cannot redeem zero amount");
+
+            let (collateral, share) = self._split_fractional(pool_id, collateral_state.ratio, amount, false);
+            assert!(collateral >= min_collateral_out && share >= min_share_out, "Treasury: collateral ratio moved");
+            assert!(collateral <= collateral_state.reserve, "Treasury: collateral reserve is not enough");
+
+            // Burn the user's cash.
             let sender = Self::env().caller();
-            let rented:bool = self.status.get(&(block_num, sender)).copied().unwrap_or(false);
-            assert!(!rented, "Boardroom: : _check_same_sender_rented err");
+            let ret: bool = self._cash(&pool).burn_from(sender, amount).is_ok();
+            assert!(ret, "Treasury: redeem_fractional burn err");
+
+            if collateral > 0 {
+                collateral_state.reserve = collateral_state.reserve.checked_sub(collateral).expect("failed at redeem_fractional the `treasury` contract");
+                self.collaterals.insert(pool_id, collateral_state);
+                let ret: bool = self._a_usd().transfer(sender, collateral).is_ok();
+                assert!(ret, "Treasury: redeem_fractional transfer err");
+            }
+
+            if share > 0 {
+                let ret: bool = self.share.mint(sender, share).is_ok();
+                assert!(ret, "Treasury: redeem_fractional mint err");
+            }
+
+            self.env().emit_event(RedeemedFractional {
+                pool_id,
+                from: Some(sender),
+                amount,
+                collateral,
+                share,
+            });
+
+            self._guard_exit();
         }
 
-        fn _update_sender_rented_status(&mut self) {
-            let block_num:u32 = Self::env().block_number();
+        /// Get the cap and the state of the ALS dilution sale, shared by all the pools.
+        #[ink(message)]
+        pub fn get_share_sale(&self) -> ShareSale {
+            return self.share_sale.clone();
+        }
+
+        /// Set the cap of the ALS dilution sale: the max ALS sold per epoch, and the max ALS inflation per epoch
+        /// in basis point of the supply. The cap is shared by all the pools.
+        #[ink(message)]
+        pub fn set_share_sale(&mut self, epoch_cap: u128, max_inflation_rate: u128) {
+            self._only_operator();
+            assert!(max_inflation_rate <= MAX_SHARE_INFLATION_RATE, "Treasury: max inflation rate is too high");
+            self.share_sale.epoch_cap = epoch_cap;
+            self.share_sale.max_inflation_rate = max_inflation_rate;
+
+            self.env().emit_event(ShareSaleChanged {
+                epoch_cap,
+                max_inflation_rate,
+            });
+        }
+
+        /// Get whether the pool's ALS dilution sale is enabled and its trigger price.
+        #[ink(message)]
+        pub fn get_share_sale_trigger(&self, pool_id: u32) -> ShareSaleTrigger {
+            return self._pool(pool_id).share_sale_trigger;
+        }
+
+        /// Set the pool's ALS dilution sale: whether it is enabled, and the trigger price relative to the target price
+        /// in basis point below which the sale opens.
+        #[ink(message)]
+        pub fn set_share_sale_trigger(&mut self, pool_id: u32, enabled: bool, trigger_rate: u128) {
+            self._only_operator();
+            assert!(trigger_rate <= BASIS_POINT, "Treasury: trigger should not above the target");
+            let mut pool = self._pool(pool_id);
+            pool.share_sale_trigger.enabled = enabled;
+            pool.share_sale_trigger.trigger_rate = trigger_rate;
+            self.pools.insert(pool_id, pool);
+
+            self.env().emit_event(ShareSaleTriggerChanged {
+                pool_id,
+                enabled,
+                trigger_rate,
+            });
+        }
+
+        /// When the cash's price < the trigger price, user can buy the newly minted ALS with the cash, the cash is burnt.
+        /// The cash is valued at its oracle's price, and the ALS is valued at the oracle's ALS price.
+        #[ink(message)]
+        pub fn sell_share(&mut self, pool_id: u32, amount: u128, min_share_out: u128) {
+            self._when_not_paused(Operation::SellShare);
+            self._not_migrated();
+            let pool = self._pool(pool_id);
+            self._check_operator(&pool);
+            self._guard_enter(pool_id);
+            assert!(pool.share_sale_trigger.enabled, "Treasury: share sale is disabled");
+            assert!(amount > 0, "Treasury: cannot sell zero amount");
+
+            let oracle = self._oracle(&pool);
+            let cash_price: u128 = oracle.get_cash_price();
+            let trigger_price: u128 = self._relative_price(pool_id, pool.share_sale_trigger.trigger_rate);
+            assert!(cash_price < trigger_price, "Treasury: cash_price not eligible for share sale");
+
+            let share_price: u128 = oracle.get_share_price();
+            assert!(share_price > 0, "Treasury: share price is not set");
+            let mul_value = amount.checked_mul(cash_price).expect("failed at sell_share the `treasury` contract");
+            let share = mul_value.checked_div(share_price).expect("failed at sell_share the `treasury` contract");
+            assert!(share > 0 && share >= min_share_out, "Treasury: share price moved");
+
+            // reset the cap when a new epoch begins, the inflation is limited against the supply at the first sale.
+            // the cap is counted across all the pools.
+            let epoch: u32 = self.epoch();
+            let mut sale = self.share_sale.clone();
+            if sale.epoch != epoch || sale.epoch_supply == 0 {
+                sale.epoch = epoch;
+                sale.epoch_supply = self.share.total_supply();
+                sale.sold = 0;
+            }
+
+            let sold: u128 = sale.sold.checked_add(share).expect("failed at sell_share the `treasury` contract");
+            assert!(sold <= sale.epoch_cap, "Treasury: amount exceeds share sale cap");
+            let mul_value = sale.epoch_supply.checked_mul(sale.max_inflation_rate).expect("failed at sell_share the `treasury` contract");
+            let max_inflation = mul_value.checked_div(BASIS_POINT).expect("failed at sell_share the `treasury` contract");
+            assert!(sold <= max_inflation, "Treasury: amount exceeds max share inflation");
+
+            sale.sold = sold;
+            sale.total_sold = sale.total_sold.checked_add(share).expect("failed at sell_share the `treasury` contract");
+            self.share_sale = sale;
+
+            // Burn the user's cash.
             let sender = Self::env().caller();
-            self.status.insert((block_num, sender), true);
+            let burn_ret: bool = self._cash(&pool).burn_from(sender, amount).is_ok();
+            assert!(burn_ret, "Treasury: sell_share burn err");
+
+            // Mint the ALS to user.
+            let ret: bool = self.share.mint(sender, share).is_ok();
+            assert!(ret, "Treasury: sell_share mint err");
+
+            self.env().emit_event(ShareSold {
+                pool_id,
+                from: Some(sender),
+                epoch,
+                amount,
+                share,
+            });
+
+            self._guard_exit();
         }
 
-        /// If the ALC's price < $1, we assume the ALC's price is 0.9. User may buy the ALB with the ALC's current price(0.9*0.9).
-        /// When the ALC's price >= $1, user can redeem the ALB, system will transfer the ALC to user with the ALC's current price.
+        /// If the cash's price < the target price, e.g. $1, we assume the cash's price is 0.9. User may buy the bond with the cash's current price(0.9*0.9).
+        /// When the cash's price >= the ceiling price, user can redeem the bond, system will transfer the cash to user with the cash's current price.
+        ///
+        /// # Errors
+        ///
+        /// Returns `Expired` error if the block time is after `deadline`(ms).
+        ///
+        /// Returns `PriceMoved` error if the cash's price is above `max_price`.
+        ///
+        /// Returns `InsufficientBondOut` error if the bonds bought are less than `min_bond_out`.
         #[ink(message)]
-        pub fn buy_bonds(&mut self, amount: u128, target_price: u128) {
-            self._check_operator();
-            self._check_same_sender_rented();
+        pub fn buy_bonds(&mut self, pool_id: u32, amount: u128, max_price: u128, min_bond_out: u128, deadline: u64) -> Result<()> {
+            self._when_not_paused(Operation::BuyBonds);
+            self._not_migrated();
+            let mut pool = self._pool(pool_id);
+            self._check_operator(&pool);
+            assert!(!self._auction(pool_id).enabled, "Treasury: bonds are sold by auction");
             assert!(amount > 0, "Treasury: cannot purchase bonds with zero amount");
+            if Self::env().block_timestamp() > deadline {
+                return Err(Error::Expired);
+            }
 
-            // get ALC's price from oracle.
-            let cash_price:u128 = self.oracle.get_cash_price();
-            assert!(cash_price <= target_price, "Treasury: cash price moved");
+            // get cash's price from oracle.
+            let cash_price:u128 = self._oracle(&pool).get_cash_price();
+            if cash_price > max_price {
+                return Err(Error::PriceMoved);
+            }
 
-            let cash_price_one = self.util.get_one_unit_with_decimal();
-            assert!(cash_price < cash_price_one, "Treasury: cash_price not eligible for bond purchase");
+            let target_price = self.get_target_price(pool_id);
+            assert!(cash_price < target_price, "Treasury: cash_price not eligible for bond purchase");
 
-            // Caculate the user's ALC amount which should be burnt.
-            self._update_conversion_limit(cash_price);
+            // Caculate the user's cash amount which should be burnt.
+            self._update_conversion_limit(pool_id, &mut pool, cash_price);
 
-            let mul_value = self.bond_cap.checked_mul(cash_price).expect("failed at buyBonds the `treasury` contract");
+            let mul_value = pool.bond_cap.checked_mul(cash_price).expect("failed at buyBonds the `treasury` contract");
 
-            let one_unit_with_decimal = self.util.get_one_unit_with_decimal();
-            let div_value = mul_value.checked_div(one_unit_with_decimal).expect("failed at buyBonds the `treasury` contract");
+            let div_value = mul_value.checked_div(target_price).expect("failed at buyBonds the `treasury` contract");
             let amount = self.util.math_min(amount, div_value);
 
             assert!(amount > 0, "Treasury: amount exceeds bond cap");
 
-            let mul_value = amount.checked_mul(one_unit_with_decimal).expect("failed at buyBonds the `treasury` contract");
+            let sender = Self::env().caller();
+            let amount = self._bond_purchase_allowance(pool_id, sender, amount);
+            assert!(amount > 0, "Treasury: amount exceeds account limit");
+
+            let mul_value = amount.checked_mul(target_price).expect("failed at buyBonds the `treasury` contract");
             let div_value = mul_value.checked_div(cash_price).expect("failed at buyBonds the `treasury` contract");
+            if div_value < min_bond_out {
+                return Err(Error::InsufficientBondOut);
+            }
 
-            // Burn the user's ALC.
-            let sender = Self::env().caller();
-            let burn_ret:bool = self.cash.burn_from(sender, amount).is_ok();
+            // enter the guard after the errors are returned, the storage is not reverted by an error.
+            self._guard_enter(pool_id);
+            self.pools.insert(pool_id, pool.clone());
+            self._record_bond_purchase(pool_id, sender, amount);
+
+            // Burn the user's cash.
+            let burn_ret:bool = self._cash(&pool).burn_from(sender, amount).is_ok();
             assert!(burn_ret, "Treasury: transfer ok");
 
-            // Mint the ALB to user.
-            let mint:bool = self.bond.mint(sender, div_value).is_ok();
+            // Mint the bond to user.
+            let mint:bool = self._bond(&pool).mint(sender, div_value).is_ok();
             assert!(mint, "Treasury: mint ok");
+            let epoch: u32 = self.epoch();
+            self._record_bonds_sold(pool_id, epoch, div_value);
 
             // Emit the event.
             self.env().emit_event(BoughtBonds {
+                pool_id,
+                from: Some(sender),
+                amount,
+            });
+
+            // release the guard, buy_bonds is limited to once per block.
+            self._guard_exit();
+            Ok(())
+        }
+
+        /// Get the limits of the bond purchase of the pool.
+        #[ink(message)]
+        pub fn get_bond_limit(&self, pool_id: u32) -> BondLimit {
+            return self._bond_limit(pool_id);
+        }
+
+        /// Set the limits of the bond purchase of the pool: the max cash an account can spend on the bonds per epoch,
+        /// and the length(ms) of the allowlist phase at the beginning of each epoch.
+        #[ink(message)]
+        pub fn set_bond_limit(&mut self, pool_id: u32, account_cap: u128, allowlist_duration: u64) {
+            self._only_operator();
+            self._pool(pool_id);
+            assert!(allowlist_duration < self.period, "Treasury: allowlist phase is too long");
+            self.bond_limits.insert(pool_id, BondLimit {
+                account_cap,
+                allowlist_duration,
+            });
+        }
+
+        /// Whether the account can buy the bonds of the pool in the allowlist phase.
+        #[ink(message)]
+        pub fn is_bond_allowlisted(&self, pool_id: u32, account: AccountId) -> bool {
+            return self.bond_allowlist.get(&(pool_id, account)).copied().unwrap_or(false);
+        }
+
+        /// Add the accounts to the allowlist of the pool, or remove them.
+        #[ink(message)]
+        pub fn set_bond_allowlist(&mut self, pool_id: u32, accounts: Vec<AccountId>, allowed: bool) {
+            self._only_operator();
+            self._pool(pool_id);
+            for account in accounts.iter() {
+                if allowed {
+                    self.bond_allowlist.insert((pool_id, *account), true);
+                } else {
+                    self.bond_allowlist.take(&(pool_id, *account));
+                }
+            }
+        }
+
+        /// Get the cash the account spent on the bonds of the pool in this epoch.
+        #[ink(message)]
+        pub fn get_bond_purchased(&self, pool_id: u32, account: AccountId) -> u128 {
+            match self.bond_purchases.get(&(pool_id, account)) {
+                Some((epoch, purchased)) if *epoch == self.epoch() => *purchased,
+                _ => 0,
+            }
+        }
+
+        /// Get the config and the state of the bond auction of the pool.
+        #[ink(message)]
+        pub fn get_auction(&self, pool_id: u32) -> Auction {
+            return self._auction(pool_id);
+        }
+
+        /// Set the bond auction of the pool: whether it is enabled, the start and the floor price in basis point of one ALC,
+        /// and the time(ms) the price takes to descend to the floor.
+        #[ink(message)]
+        pub fn set_auction(&mut self, pool_id: u32, enabled: bool, start_rate: u128, floor_rate: u128, duration: u64) {
+            self._only_operator();
+            self._pool(pool_id);
+            assert!(start_rate >= floor_rate && floor_rate > 0, "Treasury: invalid auction price");
+            let mut auction = self._auction(pool_id);
+            auction.enabled = enabled;
+            auction.start_rate = start_rate;
+            auction.floor_rate = floor_rate;
+            auction.duration = duration;
+            self.auctions.insert(pool_id, auction);
+        }
+
+        /// Get the round of the bond auction of the pool in the epoch.
+        #[ink(message)]
+        pub fn get_auction_round(&self, pool_id: u32, epoch: u32) -> Option<AuctionRound> {
+            return self.auction_rounds.get(&(pool_id, epoch)).cloned();
+        }
+
+        /// Get the current price(ALC per ALB) of the bond auction of the pool, 0 if no round is opened in this epoch.
+        #[ink(message)]
+        pub fn get_auction_price(&self, pool_id: u32) -> u128 {
+            let auction = self._auction(pool_id);
+            match self.auction_rounds.get(&(pool_id, self.epoch())) {
+                Some(round) => self._auction_price(&auction, round, Self::env().block_timestamp()),
+                None => 0,
+            }
+        }
+
+        /// Get the clearing price of the round, 0 if the round is not settled yet.
+        #[ink(message)]
+        pub fn get_clearing_price(&self, pool_id: u32, epoch: u32) -> u128 {
+            let auction = self._auction(pool_id);
+            match self.auction_rounds.get(&(pool_id, epoch)) {
+                Some(round) if round.clearing_price > 0 || epoch < self.epoch() => self._clearing_price(&auction, round),
+                _ => 0,
+            }
+        }
+
+        /// Get the cash the account committed to the round.
+        #[ink(message)]
+        pub fn get_auction_bid(&self, pool_id: u32, epoch: u32, account: AccountId) -> u128 {
+            return self.auction_bids.get(&(pool_id, epoch, account)).copied().unwrap_or(0);
+        }
+
+        /// Commit the cash to the bond auction of the current epoch at the current price, the cash is burnt.
+        /// The round is opened by the first bid of the epoch. When the committed cash buys the whole capacity at
+        /// the current price, the round is filled and the bid is cut to what is left.
+        /// The bonds are claimed by claim_auction_bonds at the uniform clearing price after the round is settled.
+        #[ink(message)]
+        pub fn bid_bonds(&mut self, pool_id: u32, amount: u128, max_price: u128) {
+            self._when_not_paused(Operation::BuyBonds);
+            self._not_migrated();
+            let mut pool = self._pool(pool_id);
+            self._check_operator(&pool);
+            self._guard_enter(pool_id);
+            let mut auction = self._auction(pool_id);
+            assert!(auction.enabled, "Treasury: auction is disabled");
+            assert!(amount > 0, "Treasury: cannot purchase bonds with zero amount");
+
+            let epoch: u32 = self.epoch();
+            let mut round = match self.auction_rounds.get(&(pool_id, epoch)).cloned() {
+                Some(round) => round,
+                None => {
+                    let cash_price: u128 = self._oracle(&pool).get_cash_price();
+                    assert!(cash_price < self.get_target_price(pool_id), "Treasury: cash_price not eligible for bond purchase");
+                    let round = self._open_auction_round(pool_id, &mut pool, &mut auction, epoch, cash_price);
+                    self.pools.insert(pool_id, pool.clone());
+                    self.auctions.insert(pool_id, auction.clone());
+                    round
+                }
+            };
+            assert!(round.clearing_price == 0, "Treasury: auction is filled");
+
+            // the price only descends, a bid whose limit is lower than the current price should wait.
+            let price: u128 = self._auction_price(&auction, &round, Self::env().block_timestamp());
+            assert!(price <= max_price, "Treasury: auction price is above the limit");
+
+            let one_unit_with_decimal = self.util.get_one_unit_with_decimal();
+            let mul_value = round.capacity.checked_mul(price).expect("failed at bid_bonds the `treasury` contract");
+            let capacity_value = mul_value.checked_div(one_unit_with_decimal).expect("failed at bid_bonds the `treasury` contract");
+            let room = capacity_value.checked_sub(self.util.math_min(capacity_value, round.committed)).expect("failed at bid_bonds the `treasury` contract");
+            assert!(room > 0, "Treasury: auction is filled");
+
+            let sender = Self::env().caller();
+            let amount = self._bond_purchase_allowance(pool_id, sender, self.util.math_min(amount, room));
+            assert!(amount > 0, "Treasury: amount exceeds account limit");
+            self._record_bond_purchase(pool_id, sender, amount);
+
+            round.committed = round.committed.checked_add(amount).expect("failed at bid_bonds the `treasury` contract");
+            if amount == room {
+                round.clearing_price = price;
+            }
+            self.auction_rounds.insert((pool_id, epoch), round);
+
+            let bid = self.get_auction_bid(pool_id, epoch, sender).checked_add(amount).expect("failed at bid_bonds the `treasury` contract");
+            self.auction_bids.insert((pool_id, epoch, sender), bid);
+
+            // Burn the user's cash.
+            let burn_ret: bool = self._cash(&pool).burn_from(sender, amount).is_ok();
+            assert!(burn_ret, "Treasury: bid_bonds burn err");
+
+            self.env().emit_event(BondsBid {
+                pool_id,
                 from: Some(sender),
+                epoch,
                 amount,
+                price,
             });
 
-            // ensure only buy_bonds once per block.
-            self._update_sender_rented_status();
+            self._guard_exit();
+        }
+
+        /// Claim the bonds of the settled round at the clearing price. A round is settled when it is filled or its epoch ends.
+        /// The bonds can still be claimed after the migration.
+        #[ink(message)]
+        pub fn claim_auction_bonds(&mut self, pool_id: u32, epoch: u32) {
+            self._when_not_paused(Operation::BuyBonds);
+            let mut pool = self._pool(pool_id);
+            let clearing_price: u128 = self.get_clearing_price(pool_id, epoch);
+            assert!(clearing_price > 0, "Treasury: auction is not settled");
+
+            let sender = Self::env().caller();
+            let bid: u128 = self.auction_bids.take(&(pool_id, epoch, sender)).unwrap_or(0);
+            assert!(bid > 0, "Treasury: no bid to claim");
+
+            let one_unit_with_decimal = self.util.get_one_unit_with_decimal();
+            let mul_value = bid.checked_mul(one_unit_with_decimal).expect("failed at claim_auction_bonds the `treasury` contract");
+            let bonds = mul_value.checked_div(clearing_price).expect("failed at claim_auction_bonds the `treasury` contract");
+
+            // the claimed bonds are minted and not owed anymore.
+            self._settle_auction_round(pool_id, &mut pool, epoch);
+            pool.auction_bonds_owed = pool.auction_bonds_owed.checked_sub(self.util.math_min(pool.auction_bonds_owed, bonds)).expect("failed at claim_auction_bonds the `treasury` contract");
+            self.pools.insert(pool_id, pool.clone());
+
+            // Mint the bond to user, or transfer the bond minted by the migration.
+            if self.migrated {
+                let ret: bool = self._bond(&pool).transfer(sender, bonds).is_ok();
+                assert!(ret, "Treasury: claim_auction_bonds transfer err");
+            } else {
+                let mint: bool = self._bond(&pool).mint(sender, bonds).is_ok();
+                assert!(mint, "Treasury: claim_auction_bonds mint err");
+            }
+            self._record_bonds_sold(pool_id, epoch, bonds);
+
+            self.env().emit_event(AuctionBondsClaimed {
+                pool_id,
+                from: Some(sender),
+                epoch,
+                amount: bonds,
+                price: clearing_price,
+            });
         }
 
-        /// When the ALC's price >= $1.05, user can redeem the ALB, system will transfer the ALC to user with the ALC's current price.
+        /// When the cash's price >= the ceiling price(target price * 1.05 by default), user can redeem the bond, system will transfer the cash to user with the cash's current price.
+        /// The bond is redeemed 1:1 for the cash, if the treasury has less cash than `amount`, only the cash of the treasury is redeemed.
+        ///
+        /// # Errors
+        ///
+        /// Returns `Expired` error if the block time is after `deadline`(ms).
+        ///
+        /// Returns `InsufficientCashOut` error if the cash paid is less than `min_cash_out`.
         #[ink(message)]
-        pub fn redeem_bonds(&mut self, amount: u128) {
-            self._check_operator();
-            self._check_same_sender_rented();
+        pub fn redeem_bonds(&mut self, pool_id: u32, amount: u128, min_cash_out: u128, deadline: u64) -> Result<()> {
+            self._when_not_paused(Operation::RedeemBonds);
+            self._not_migrated();
+            let mut pool = self._pool(pool_id);
+            self._check_operator(&pool);
             assert!(amount > 0, "Treasury: cannot redeem bonds with zero amount");
+            if Self::env().block_timestamp() > deadline {
+                return Err(Error::Expired);
+            }
 
-            let cash_price:u128 = self.oracle.get_cash_price();
-            let ceiling_price:u128 = self.util.get_ceiling_price();
+            let cash_price:u128 = self._oracle(&pool).get_cash_price();
+            let ceiling_price:u128 = self.get_ceiling_price(pool_id);
             assert!(cash_price > ceiling_price, "Treasury: cashPrice not eligible for bond purchase");
 
-            let b: u128 = self._cash_balance_of_this();
-            assert!(b >= amount, "Treasury: treasury has no more budget");
+            // the redemption is filled partially by the budget of the treasury.
+            let b: u128 = self._cash_balance_of_this(&pool);
+            let paid: u128 = self.util.math_min(amount, b);
+            assert!(paid > 0, "Treasury: treasury has no more budget");
+            if paid < min_cash_out {
+                return Err(Error::InsufficientCashOut);
+            }
+
+            // enter the guard after the errors are returned, the storage is not reverted by an error.
+            self._guard_enter(pool_id);
 
-            let sub_value = self.accumulated_seigniorage.checked_sub(self.util.math_min(self.accumulated_seigniorage, amount)).expect("failed at redeemBonds the `treasury` contract");
-            self.accumulated_seigniorage = sub_value;
+            let sub_value = pool.accumulated_seigniorage.checked_sub(self.util.math_min(pool.accumulated_seigniorage, paid)).expect("failed at redeemBonds the `treasury` contract");
+            pool.accumulated_seigniorage = sub_value;
+            self.pools.insert(pool_id, pool.clone());
 
-            // Burn the user's ALB.
+            // Burn the user's bond.
             let sender = Self::env().caller();
-            let burn_ret: bool = self.bond.burn_from(sender, amount).is_ok();
+            let burn_ret: bool = self._bond(&pool).burn_from(sender, paid).is_ok();
             assert!(burn_ret, "Treasury: transfer ok");
 
-            // Transfer the ALC to user.
-            let trans_ret: bool = self.cash.transfer(sender, amount).is_ok();
+            // Transfer the cash to user.
+            let trans_ret: bool = self._cash(&pool).transfer(sender, paid).is_ok();
             assert!(trans_ret, "Treasury: transfer ok");
 
+            let epoch: u32 = self.epoch();
+            let mut record = self._epoch_record(pool_id, epoch);
+            record.bonds_redeemed = record.bonds_redeemed.checked_add(paid).expect("failed at redeemBonds the `treasury` contract");
+            self.epoch_records.insert((pool_id, epoch), record);
+
             // Emit the event.
             self.env().emit_event(RedeemedBonds {
+                pool_id,
                 from: Some(sender),
-                amount,
+                amount: paid,
             });
 
-            // ensure only redeem_bonds once per block.
-            self._update_sender_rented_status();
+            // release the guard, redeem_bonds is limited to once per block.
+            self._guard_exit();
+            Ok(())
         }
 
-        /// Allocate the ALC to boardroom, the other is the reward of user who stake the ALS.
+        /// Allocate the cash to boardroom, the other is the reward of user who stake the ALS.
+        /// It can be called once per epoch for each pool, and the caller is paid with the keeper reward.
         #[ink(message)]
-        pub fn allocate_seigniorage(&mut self) {
-            self._check_operator();
-            self._check_same_sender_rented();
-            let cash_price:u128 = self.oracle.get_cash_price();
-            let ceiling_price:u128 = self.util.get_ceiling_price();
+        pub fn allocate_seigniorage(&mut self, pool_id: u32) {
+            self._when_not_paused(Operation::AllocateSeigniorage);
+            self._not_migrated();
+            let mut pool = self._pool(pool_id);
+            self._check_operator(&pool);
+            let epoch: u32 = self.epoch();
+            assert!(epoch >= pool.next_allocation_epoch, "Treasury: not opened yet");
+            self._guard_enter(pool_id);
+            let cash_price:u128 = self._oracle(&pool).get_cash_price();
+            let ceiling_price:u128 = self.get_ceiling_price(pool_id);
             assert!(cash_price > ceiling_price, "Treasury: cashPrice not eligible for allocate_seigniorage");
 
             // circulating supply
-            let cash_price_one = self.util.get_one_unit_with_decimal();
-            let percentage:u128 = cash_price.checked_sub(cash_price_one).expect("failed at allocateSeigniorage the `treasury` contract");
-            let seigniorage_mul:u128 = self._circulating_supply().checked_mul(percentage).expect("failed at allocateSeigniorage the `treasury` contract");
-            let seigniorage:u128 = seigniorage_mul.checked_div(cash_price_one).expect("failed at allocateSeigniorage the `treasury` contract");
+            let target_price = self.get_target_price(pool_id);
+            let percentage:u128 = cash_price.checked_sub(target_price).expect("failed at allocateSeigniorage the `treasury` contract");
+            let seigniorage_mul:u128 = self._circulating_supply(&pool).checked_mul(percentage).expect("failed at allocateSeigniorage the `treasury` contract");
+            let seigniorage:u128 = seigniorage_mul.checked_div(target_price).expect("failed at allocateSeigniorage the `treasury` contract");
 
-            assert!(seigniorage > 0, "seigniorage should above 0");    
+            assert!(seigniorage > 0, "seigniorage should above 0");
 
-            // mint the ALC.
+            let mut record = self._epoch_record(pool_id, epoch);
+            record.cash_price = cash_price;
+            record.seigniorage = seigniorage;
+
+            // mint the cash.
             let this = self.env().account_id();
-            let mint_ret:bool = self.cash.mint(this, seigniorage).is_ok();
+            let mut cash = self._cash(&pool);
+            let mint_ret:bool = cash.mint(this, seigniorage).is_ok();
             assert!(mint_ret, "Treasury: allocate_seigniorage mint err");
 
-            let bond_total:u128 = self.bond.total_supply();
-            let bond_total_sub:u128 = bond_total.checked_sub(self.accumulated_seigniorage).expect("failed at allocateSeigniorage the `treasury` contract");
+            // pay the keeper, it is not a part of the treasury reserve or the boardroom.
+            let sender = Self::env().caller();
+            let keeper_reward: u128 = self._keeper_reward(seigniorage);
+            if keeper_reward > 0 {
+                let ret: bool = cash.transfer(sender, keeper_reward).is_ok();
+                assert!(ret, "Treasury: allocate_seigniorage keeper reward err");
+                pool.keeper_rewards_paid = pool.keeper_rewards_paid.checked_add(keeper_reward).expect("failed at allocateSeigniorage the `treasury` contract");
+                self.env().emit_event(KeeperRewarded {
+                    pool_id,
+                    keeper: sender,
+                    epoch,
+                    reward: keeper_reward,
+                });
+            }
+            let seigniorage: u128 = seigniorage.checked_sub(keeper_reward).expect("failed at allocateSeigniorage the `treasury` contract");
+            record.keeper_reward = keeper_reward;
+
+            let bond_total:u128 = self._bond(&pool).total_supply();
+            let bond_total_sub:u128 = bond_total.checked_sub(pool.accumulated_seigniorage).expect("failed at allocateSeigniorage the `treasury` contract");
             let treasury_reserve_ori = self.util.math_min(seigniorage, bond_total_sub);
             let mut treasury_reserve: u128 = 0;
             if treasury_reserve_ori > 0 {
@@ -277,27 +2231,66 @@ mod treasury {
                     let treasury_reserve_mul:u128 = treasury_reserve_ori.checked_mul(80).expect("failed at allocateSeigniorage the `treasury` contract");
                     treasury_reserve = treasury_reserve_mul.checked_div(100).expect("failed at allocateSeigniorage the `treasury` contract");
                 }
-                self.accumulated_seigniorage = self.accumulated_seigniorage.checked_add(treasury_reserve).expect("failed at allocateSeigniorage the `treasury` contract");
+                pool.accumulated_seigniorage = pool.accumulated_seigniorage.checked_add(treasury_reserve).expect("failed at allocateSeigniorage the `treasury` contract");
+                record.treasury_reserve = treasury_reserve;
                 self.env().emit_event(TreasuryFunded {
+                    pool_id,
                     timestamp: Self::env().block_timestamp(),
                     seigniorage: treasury_reserve,
                 });
             }
 
             // boardroom
+            let mut boardroom = self._boardroom(&pool);
             let boardroom_reserve:u128 = seigniorage.checked_sub(treasury_reserve).expect("failed at allocateSeigniorage the `treasury` contract");
-            if boardroom_reserve > 0 {
-                let ret:bool = self.cash.approve(self.room_address, boardroom_reserve).is_ok();
+            if boardroom_reserve > 0 && boardroom.total_supply() == 0 {
+                // nobody can receive the reward, reroute it to the DAO fund or the treasury reserve.
+                let rerouted: u128 = match self.dao_fund {
+                    Some(fund) => {
+                        let ret: bool = cash.transfer(fund, boardroom_reserve).is_ok();
+                        assert!(ret, "Treasury: allocate_seigniorage transfer to dao fund err");
+                        record.dao_fund_reserve = boardroom_reserve;
+                        boardroom_reserve
+                    }
+                    None => {
+                        // the treasury reserve can't exceed the ALB to redeem, the rest is burnt.
+                        let room: u128 = bond_total.checked_sub(pool.accumulated_seigniorage).expect("failed at allocateSeigniorage the `treasury` contract");
+                        let top_up: u128 = self.util.math_min(boardroom_reserve, room);
+                        pool.accumulated_seigniorage = pool.accumulated_seigniorage.checked_add(top_up).expect("failed at allocateSeigniorage the `treasury` contract");
+                        record.treasury_reserve = record.treasury_reserve.checked_add(top_up).expect("failed at allocateSeigniorage the `treasury` contract");
+
+                        let excess: u128 = boardroom_reserve.checked_sub(top_up).expect("failed at allocateSeigniorage the `treasury` contract");
+                        if excess > 0 {
+                            let ret: bool = cash.burn(excess).is_ok();
+                            assert!(ret, "Treasury: allocate_seigniorage burn err");
+                        }
+                        top_up
+                    }
+                };
+                self.env().emit_event(BoardroomFundsRerouted {
+                    pool_id,
+                    timestamp: Self::env().block_timestamp(),
+                    fund: self.dao_fund,
+                    seigniorage: rerouted,
+                });
+            } else if boardroom_reserve > 0 {
+                let ret:bool = cash.approve(pool.boardroom, boardroom_reserve).is_ok();
                 assert!(ret, "Treasury: allocate_seigniorage approve err");
 
-                // allocate the ALC as reward to user.
-                self.boardroom.allocate_seigniorage(boardroom_reserve);
+                // allocate the cash as reward to user.
+                boardroom.allocate_seigniorage(boardroom_reserve);
+                record.boardroom_reserve = boardroom_reserve;
                 self.env().emit_event(BoardroomFunded {
+                    pool_id,
                     timestamp: Self::env().block_timestamp(),
-                    seigniorage: treasury_reserve,
+                    seigniorage: boardroom_reserve,
                 });
             }
-            self._update_sender_rented_status();
+            self.epoch_records.insert((pool_id, epoch), record);
+
+            pool.next_allocation_epoch = epoch.checked_add(1).expect("failed at allocateSeigniorage the `treasury` contract");
+            self.pools.insert(pool_id, pool);
+            self._guard_exit();
         }
     }
 }
@@ -0,0 +1,445 @@
+// Copyright 2018-2021 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+pub use self::vault::Vault;
+use ink_lang as ink;
+
+#[ink::contract]
+mod vault {
+    use ink_storage::Lazy;
+    use ink_env::call::{FromAccountId, ToAccountId};
+
+    use asset::Asset;
+    use boardroom::Boardroom;
+    use oracle::Oracle;
+    use tokenstub::TokenStub;
+    use pairstub::PairStub;
+
+    /// The denominator of the basis point.
+    const BASIS_POINT: u128 = 10000;
+
+    /// The shares minted to the vault itself by the first deposit and never burnt, so the price of a share
+    /// can't be inflated by a donation to the empty vault.
+    const DEAD_SHARES: u128 = 1000;
+
+    /// The default part of the ALS kept idle for the withdrawals, in basis point.
+    const DEFAULT_BUFFER_RATE: u128 = 1000;
+
+    /// The default max slippage of the harvest swap from the oracle prices, in basis point.
+    const DEFAULT_MAX_SLIPPAGE: u128 = 100;
+
+    /// Event emitted when user deposit the ALS and get the vault shares.
+    #[ink(event)]
+    pub struct Deposited {
+        #[ink(topic)]
+        user: Option<AccountId>,
+        #[ink(topic)]
+        amount: u128,
+        shares: u128,
+    }
+
+    /// Event emitted when user burn the vault shares and get back the ALS.
+    #[ink(event)]
+    pub struct Withdrawn {
+        #[ink(topic)]
+        user: Option<AccountId>,
+        #[ink(topic)]
+        amount: u128,
+        shares: u128,
+    }
+
+    /// Event emitted when the ALC reward is claimed, swapped for ALS and restaked.
+    #[ink(event)]
+    pub struct Harvested {
+        #[ink(topic)]
+        caller: Option<AccountId>,
+        reward: u128,
+        staked: u128,
+    }
+
+    /// Event emitted when the operator switch the AMM pair.
+    #[ink(event)]
+    pub struct PairChanged {
+        #[ink(topic)]
+        pair: Option<AccountId>,
+    }
+
+    /// Event emitted when the operator change the withdrawal buffer or the max slippage.
+    #[ink(event)]
+    pub struct ConfigChanged {
+        buffer_rate: u128,
+        max_slippage: u128,
+    }
+
+    /// Event emitted when the operator recover the token sent to this contract by mistake.
+    #[ink(event)]
+    pub struct UnsupportedRecovered {
+        #[ink(topic)]
+        token: AccountId,
+        #[ink(topic)]
+        to: AccountId,
+        amount: u128,
+    }
+
+    /// The vault holds a pooled ALS position in the Boardroom. The ALC reward is swapped for ALS through the AMM pair
+    /// and restaked, so the ALS of a vault share grows.
+    /// The vault must be the operator of the vault share token to mint and burn it.
+    ///
+    /// Every stake of the vault starts its withdraw lockup in the Boardroom again, and the Boardroom allows the vault
+    /// one stake or withdraw per block. So `buffer_rate` of the ALS is kept idle to pay the withdrawals, and
+    /// the Boardroom is only used when the buffer runs out.
+    #[ink(storage)]
+    pub struct Vault {
+        cash: Lazy<Asset>,
+        share: Lazy<Asset>,
+        boardroom: Lazy<Boardroom>,
+        vault_share: Lazy<Asset>,
+        oracle: Lazy<Oracle>,
+        pair: Option<AccountId>,
+        buffer_rate: u128,
+        max_slippage: u128,
+        operator: AccountId,
+    }
+
+    impl Vault {
+        #[ink(constructor)]
+        pub fn new(cash_address: AccountId,
+                   share_address: AccountId,
+                   boardroom_address: AccountId,
+                   vault_share_address: AccountId,
+                   oracle_address: AccountId,
+                   pair: Option<AccountId>) -> Self {
+            let cash: Asset = FromAccountId::from_account_id(cash_address);
+            let share: Asset = FromAccountId::from_account_id(share_address);
+            let boardroom: Boardroom = FromAccountId::from_account_id(boardroom_address);
+            let vault_share: Asset = FromAccountId::from_account_id(vault_share_address);
+            let oracle: Oracle = FromAccountId::from_account_id(oracle_address);
+            let sender = Self::env().caller();
+
+            let instance = Self {
+                cash: Lazy::new(cash),
+                share: Lazy::new(share),
+                boardroom: Lazy::new(boardroom),
+                vault_share: Lazy::new(vault_share),
+                oracle: Lazy::new(oracle),
+                pair,
+                buffer_rate: DEFAULT_BUFFER_RATE,
+                max_slippage: DEFAULT_MAX_SLIPPAGE,
+                operator: sender,
+            };
+            instance
+        }
+
+        fn _only_operator(&self) {
+            let sender = Self::env().caller();
+            assert!(self.operator == sender, "Vault: caller is not the operator");
+        }
+
+        /// Get the ALS which can be paid for `shares` of the vault, rounded down. The ALC reward is not harvested
+        /// yet and is not paid, so it is left to the other holders.
+        fn _to_redeemable(&self, shares: u128) -> u128 {
+            let supply: u128 = self.vault_share.total_supply();
+            if supply == 0 {
+                return 0;
+            }
+            let mul: u128 = shares.checked_mul(self._redeemable_total()).expect("failed at _to_redeemable the `vault` contract");
+            return mul.checked_div(supply).expect("failed at _to_redeemable the `vault` contract");
+        }
+
+        /// Get the ALS of the vault which can be paid to the withdrawals: the staked and the idle.
+        fn _redeemable_total(&self) -> u128 {
+            return self.staked().checked_add(self.idle()).expect("failed at _redeemable_total the `vault` contract");
+        }
+
+        /// Get the ALS for `shares` of the vault, rounded down.
+        fn _to_amount(&self, shares: u128) -> u128 {
+            let supply: u128 = self.vault_share.total_supply();
+            if supply == 0 {
+                return shares;
+            }
+            let mul: u128 = shares.checked_mul(self.total_assets()).expect("failed at _to_amount the `vault` contract");
+            return mul.checked_div(supply).expect("failed at _to_amount the `vault` contract");
+        }
+
+        /// Get the ALS worth `amount` of ALC by the oracle prices.
+        fn _cash_to_share(&self, amount: u128) -> u128 {
+            let share_price: u128 = self.oracle.get_share_price();
+            if amount == 0 || share_price == 0 {
+                return 0;
+            }
+            let mul: u128 = amount.checked_mul(self.oracle.get_cash_price()).expect("failed at _cash_to_share the `vault` contract");
+            return mul.checked_div(share_price).expect("failed at _cash_to_share the `vault` contract");
+        }
+
+        /// Get the idle ALS the vault keeps for the withdrawals when it has `total` ALS.
+        fn _buffer_of(&self, total: u128) -> u128 {
+            let mul: u128 = total.checked_mul(self.buffer_rate).expect("failed at _buffer_of the `vault` contract");
+            return mul.checked_div(BASIS_POINT).expect("failed at _buffer_of the `vault` contract");
+        }
+
+        /// Whether the vault has staked or withdrawn in the Boardroom in this block.
+        fn _boardroom_used(&self) -> bool {
+            let this = self.env().account_id();
+            let block: u32 = self.env().block_number();
+            return self.boardroom.last_action_block(this) == Some(block);
+        }
+
+        /// Get the vault shares for `amount` of ALS, rounded down.
+        fn _to_shares(&self, amount: u128) -> u128 {
+            let supply: u128 = self.vault_share.total_supply();
+            let total: u128 = self.total_assets();
+            if supply == 0 || total == 0 {
+                return amount;
+            }
+            let mul: u128 = amount.checked_mul(supply).expect("failed at _to_shares the `vault` contract");
+            return mul.checked_div(total).expect("failed at _to_shares the `vault` contract");
+        }
+
+        #[ink(message)]
+        pub fn operator(&self) -> AccountId {
+            return self.operator;
+        }
+
+        #[ink(message)]
+        pub fn transfer_operator(&mut self, new_operator: AccountId) {
+            self._only_operator();
+            self.operator = new_operator;
+        }
+
+        /// Get the AMM pair which the ALC reward is swapped through.
+        #[ink(message)]
+        pub fn pair(&self) -> Option<AccountId> {
+            return self.pair;
+        }
+
+        /// Set the AMM pair, the reward is not swapped if it is none.
+        #[ink(message)]
+        pub fn set_pair(&mut self, pair: Option<AccountId>) {
+            self._only_operator();
+            self.pair = pair;
+            self.env().emit_event(PairChanged {
+                pair,
+            });
+        }
+
+        /// Get the ALS staked in the Boardroom by the vault.
+        #[ink(message)]
+        pub fn staked(&self) -> u128 {
+            let this = self.env().account_id();
+            return self.boardroom.balance_of(this);
+        }
+
+        /// Get the ALS held by the vault which is not staked yet.
+        #[ink(message)]
+        pub fn idle(&self) -> u128 {
+            let this = self.env().account_id();
+            return self.share.balance_of(this);
+        }
+
+        /// Get the ALC reward of the vault, the claimed and the pending in the Boardroom.
+        #[ink(message)]
+        pub fn reward(&self) -> u128 {
+            let this = self.env().account_id();
+            let claimed: u128 = self.cash.balance_of(this);
+            return claimed.checked_add(self.boardroom.earned(this)).expect("failed at reward the `vault` contract");
+        }
+
+        /// Get all the ALS of the vault: the staked, the idle and the ALC reward valued by the oracle prices.
+        /// The reward is counted so a deposit doesn't share the reward earned before it.
+        #[ink(message)]
+        pub fn total_assets(&self) -> u128 {
+            let total: u128 = self.staked().checked_add(self.idle()).expect("failed at total_assets the `vault` contract");
+            return total.checked_add(self._cash_to_share(self.reward())).expect("failed at total_assets the `vault` contract");
+        }
+
+        /// Get the part of the ALS kept idle for the withdrawals and the max slippage of the harvest swap, in basis point.
+        #[ink(message)]
+        pub fn get_config(&self) -> (u128, u128) {
+            return (self.buffer_rate, self.max_slippage);
+        }
+
+        /// Set the part of the ALS kept idle for the withdrawals and the max slippage of the harvest swap, in basis point.
+        #[ink(message)]
+        pub fn set_config(&mut self, buffer_rate: u128, max_slippage: u128) {
+            self._only_operator();
+            assert!(buffer_rate <= BASIS_POINT && max_slippage <= BASIS_POINT, "Vault: rate is above 100%");
+            self.buffer_rate = buffer_rate;
+            self.max_slippage = max_slippage;
+            self.env().emit_event(ConfigChanged {
+                buffer_rate,
+                max_slippage,
+            });
+        }
+
+        /// Get the ALS of `shares` of the vault.
+        #[ink(message)]
+        pub fn convert_to_assets(&self, shares: u128) -> u128 {
+            return self._to_amount(shares);
+        }
+
+        /// Get the ALS paid by withdrawing `shares` of the vault now, the ALC reward not harvested yet is not paid.
+        #[ink(message)]
+        pub fn preview_withdraw(&self, shares: u128) -> u128 {
+            return self._to_redeemable(shares);
+        }
+
+        /// Get the vault shares of `amount` of ALS.
+        #[ink(message)]
+        pub fn convert_to_shares(&self, amount: u128) -> u128 {
+            return self._to_shares(amount);
+        }
+
+        /// Deposit the ALS and get the vault shares. The ALS is idle until the next `harvest` stakes it,
+        /// so the deposits don't share the Boardroom's one action per block of the vault.
+        /// The first deposit mints `DEAD_SHARES` to the vault itself, which are never burnt.
+        #[ink(message)]
+        pub fn deposit(&mut self, amount: u128) -> u128 {
+            assert!(amount > 0, "Vault: Cannot deposit 0");
+            let this = self.env().account_id();
+            let mut shares: u128 = self._to_shares(amount);
+            if self.vault_share.total_supply() == 0 {
+                assert!(shares > DEAD_SHARES, "Vault: first deposit is too small");
+                shares = shares - DEAD_SHARES;
+                let ret: bool = self.vault_share.mint(this, DEAD_SHARES).is_ok();
+                assert!(ret, "Vault: deposit vault_share.mint err");
+            }
+            assert!(shares > 0, "Vault: Cannot mint 0 share");
+
+            let sender = Self::env().caller();
+            let ret: bool = self.share.transfer_from(sender, this, amount).is_ok();
+            assert!(ret, "Vault: deposit share.transfer_from err");
+            let ret: bool = self.vault_share.mint(sender, shares).is_ok();
+            assert!(ret, "Vault: deposit vault_share.mint err");
+
+            self.env().emit_event(Deposited {
+                user: Some(sender),
+                amount,
+                shares,
+            });
+            return shares;
+        }
+
+        /// Burn the vault shares and get back the ALS. The idle ALS is paid first. If it is not enough, the rest and
+        /// the buffer are withdrawn from the Boardroom, so it fails in the Boardroom's withdraw lockup, or if the vault
+        /// has used the Boardroom in this block and must be retried in the next block.
+        /// Only the staked and the idle ALS are paid, the share of the ALC reward not harvested yet is left in the vault.
+        /// The caller must approve the vault to burn the shares.
+        #[ink(message)]
+        pub fn withdraw(&mut self, shares: u128) -> u128 {
+            assert!(shares > 0, "Vault: Cannot withdraw 0");
+            let amount: u128 = self._to_redeemable(shares);
+            assert!(amount > 0, "Vault: Cannot withdraw 0 ALS");
+
+            let sender = Self::env().caller();
+            let ret: bool = self.vault_share.burn_from(sender, shares).is_ok();
+            assert!(ret, "Vault: withdraw vault_share.burn_from err");
+
+            let idle: u128 = self.idle();
+            if idle < amount {
+                let this = self.env().account_id();
+                assert!(self.boardroom.can_withdraw(this), "Vault: staked ALS is in the withdraw lockup");
+                assert!(!self._boardroom_used(), "Vault: boardroom is used in this block, retry in the next block");
+
+                // Refill the buffer too, so the next withdrawals are paid by the idle ALS.
+                // the amount is not more than the staked and the idle ALS, so the staked ALS is enough.
+                let left: u128 = self._redeemable_total().checked_sub(amount).expect("failed at withdraw the `vault` contract");
+                let unstaked: u128 = (amount - idle).checked_add(self._buffer_of(left)).expect("failed at withdraw the `vault` contract");
+                let staked: u128 = self.staked();
+                self.boardroom.withdraw(if unstaked < staked { unstaked } else { staked });
+            }
+            let ret: bool = self.share.transfer(sender, amount).is_ok();
+            assert!(ret, "Vault: withdraw share.transfer err");
+
+            self.env().emit_event(Withdrawn {
+                user: Some(sender),
+                amount,
+                shares,
+            });
+            return amount;
+        }
+
+        /// Claim the reward, swap the ALC for ALS through the pair and stake the idle ALS above the buffer.
+        /// Only the operator can call it, as every stake starts the vault's withdraw lockup in the Boardroom again.
+        /// The swap pays at least `min_share_out`, and at least the oracle value of the ALC less `max_slippage`,
+        /// so it can't be sandwiched. The reward is not claimed while the vault is in the Boardroom's reward lockup,
+        /// and the ALS is not staked if the vault has used the Boardroom in this block.
+        #[ink(message)]
+        pub fn harvest(&mut self, min_share_out: u128) -> u128 {
+            self._only_operator();
+            let this = self.env().account_id();
+            if self.staked() > 0 && self.boardroom.can_claim_reward(this) {
+                self.boardroom.claim_reward();
+            }
+
+            // Swap the ALC for ALS.
+            let reward: u128 = self.cash.balance_of(this);
+            if reward > 0 {
+                if let Some(pair_address) = self.pair {
+                    let oracle_out: u128 = self._cash_to_share(reward);
+                    assert!(oracle_out > 0, "Vault: oracle has no price");
+                    let oracle_min: u128 = oracle_out.checked_mul(BASIS_POINT - self.max_slippage).expect("failed at harvest the `vault` contract");
+                    let oracle_min: u128 = oracle_min.checked_div(BASIS_POINT).expect("failed at harvest the `vault` contract");
+                    let min_out: u128 = if min_share_out > oracle_min { min_share_out } else { oracle_min };
+
+                    let ret: bool = self.cash.approve(pair_address, reward).is_ok();
+                    assert!(ret, "Vault: harvest cash.approve err");
+                    // check the ALS received, not the amount the pair reports.
+                    let before: u128 = self.idle();
+                    let mut pair: PairStub = FromAccountId::from_account_id(pair_address);
+                    let cash_address: AccountId = self.cash.to_account_id();
+                    pair.swap(cash_address, reward, min_out, this);
+                    let received: u128 = self.idle().checked_sub(before).expect("failed at harvest the `vault` contract");
+                    assert!(received >= min_out, "Vault: harvest swap pays too little");
+                }
+            }
+
+            // Restake the ALS above the buffer.
+            let buffer: u128 = self._buffer_of(self.total_assets());
+            let staked: u128 = if self._boardroom_used() { 0 } else { self.idle().saturating_sub(buffer) };
+            if staked > 0 {
+                let boardroom_address: AccountId = self.boardroom.to_account_id();
+                let ret: bool = self.share.approve(boardroom_address, staked).is_ok();
+                assert!(ret, "Vault: harvest share.approve err");
+                self.boardroom.stake(staked);
+            }
+
+            let sender = Self::env().caller();
+            self.env().emit_event(Harvested {
+                caller: Some(sender),
+                reward,
+                staked,
+            });
+            return staked;
+        }
+
+        /// Transfer the token sent to this contract by mistake to `to`, e.g. the extra reward tokens of the Boardroom.
+        /// ALC and ALS can't be recovered.
+        #[ink(message)]
+        pub fn recover_unsupported(&mut self, token: AccountId, amount: u128, to: AccountId) {
+            self._only_operator();
+            assert!(token != self.cash.to_account_id() && token != self.share.to_account_id(), "Vault: protocol token can't be recovered");
+            let mut unsupported: TokenStub = FromAccountId::from_account_id(token);
+            let ret: bool = unsupported.transfer(to, amount).is_ok();
+            assert!(ret, "Vault: recover_unsupported transfer err");
+
+            self.env().emit_event(UnsupportedRecovered {
+                token,
+                to,
+                amount,
+            });
+        }
+    }
+}
@@ -76,6 +76,109 @@ describe("boardroom", () => {
         return { Alice, Bob, alc, alb, als, oracle, boardroom, treasury };
     }
 
+    async function expectFail(tx) {
+        let failed = false;
+        try {
+            await tx;
+        } catch (e) {
+            failed = true;
+        }
+        expect(failed).to.equal(true);
+    }
+
+    function sleep(ms) {
+        return new Promise(resolve => setTimeout(resolve, ms));
+    }
+
+    async function stakeAndLock(Alice, als, boardroom, treasury, decimal) {
+        await als.tx.mint(Alice, 100*decimal);
+        await als.tx.approve(boardroom.address, 100*decimal);
+        await boardroom.tx.transferOperator(treasury.address);
+
+        console.log("enable the lock mode: 5.2s at most, boost 100%");
+        await treasury.tx.setBoardroomLockMode(0, true, 5200, 10000);
+
+        console.log("alice stake and lock als");
+        await boardroom.tx.stake(100*decimal);
+        await boardroom.tx.lock(5200);
+        const weight = await boardroom.query.weightOf(Alice);
+        expect(Number(weight.output.toString())).to.be.greaterThan(100*decimal);
+        const locked = await boardroom.query.isLocked(Alice);
+        expect(locked.output).to.equal(true);
+        await expectFail(boardroom.tx.withdraw(100*decimal));
+    }
+
+    it("stake for a director only with the approval", async () => {
+        const { Alice, Bob, als, boardroom } = await setup();
+        const decimal = 10000000000;
+
+        console.log("mint als to bob");
+        await als.tx.mint(Bob, 100*decimal);
+        await als.connect(Bob).tx.approve(boardroom.address, 100*decimal);
+
+        console.log("bob can't stake for alice without the approval");
+        await expectFail(boardroom.connect(Bob).tx.stakeFor(Alice, 100*decimal));
+
+        console.log("alice approve bob, bob stake for alice");
+        await boardroom.tx.approveOperator(Bob, true);
+        await boardroom.connect(Bob).tx.stakeFor(Alice, 100*decimal);
+        const alice_stake = await boardroom.query.balanceOf(Alice);
+        expect(alice_stake.output).to.equal(100*decimal);
+        const bob_stake = await boardroom.query.balanceOf(Bob);
+        expect(bob_stake.output).to.equal(0);
+    });
+
+    it("stop the boost when the lock ends", async () => {
+        const { Alice, Bob, als, boardroom, treasury } = await setup();
+        const decimal = 10000000000;
+        await stakeAndLock(Alice, als, boardroom, treasury, decimal);
+
+        console.log("a locked account or an account without a seat can't be kicked");
+        await expectFail(boardroom.tx.kick(Alice));
+        await expectFail(boardroom.tx.kick(Bob));
+        const bob_seat = await boardroom.query.getSeat(Bob);
+        expect(bob_seat.output.isNone).to.equal(true);
+
+        console.log("wait until the lock ends");
+        await sleep(7000);
+        const locked = await boardroom.query.isLocked(Alice);
+        expect(locked.output).to.equal(false);
+
+        console.log("kick alice, the boost is gone");
+        await boardroom.tx.kick(Alice);
+        const weight = await boardroom.query.weightOf(Alice);
+        expect(weight.output).to.equal(100*decimal);
+        const total = await boardroom.query.totalWeight();
+        expect(total.output).to.equal(100*decimal);
+
+        await boardroom.tx.withdraw(100*decimal);
+        const alice_als_balance = await als.query.balanceOf(Alice);
+        expect(alice_als_balance.output).to.equal(100*decimal);
+
+        console.log("nothing is left to kick");
+        await expectFail(boardroom.tx.kick(Alice));
+    });
+
+    it("stop all the boosts when the lock mode is disabled", async () => {
+        const { Alice, als, boardroom, treasury } = await setup();
+        const decimal = 10000000000;
+        await stakeAndLock(Alice, als, boardroom, treasury, decimal);
+
+        console.log("disable the lock mode");
+        await treasury.tx.setBoardroomLockMode(0, false, 5200, 10000);
+        const total = await boardroom.query.totalWeight();
+        expect(total.output).to.equal(100*decimal);
+        const locked = await boardroom.query.isLocked(Alice);
+        expect(locked.output).to.equal(false);
+
+        console.log("withdraw all, no weight is left");
+        await boardroom.tx.withdraw(100*decimal);
+        const weight = await boardroom.query.weightOf(Alice);
+        expect(weight.output).to.equal(0);
+        const total_left = await boardroom.query.totalWeight();
+        expect(total_left.output).to.equal(0);
+    });
+
     it("Stake als, mint alc, withdraw als, claim alc reward", async () => {
         const { Alice, Bob, alc, alb, als, oracle, boardroom, treasury } = await setup();
         
@@ -124,8 +227,11 @@ describe("boardroom", () => {
         const b_price = await oracle.query.getCashPrice();
         expect(b_price.output).to.equal(11000000000);
 
+        console.log("stream the reward in 1ms");
+        await treasury.tx.setBoardroomRewardDuration(0, 1);
+
         console.log("allocate seigniorage");
-        await treasury.tx.allocateSeigniorage();
+        await treasury.tx.allocateSeigniorage(0);
 
         console.log("withdraw als");
         await boardroom.tx.withdraw(100*decimal); 
@@ -139,4 +245,299 @@ describe("boardroom", () => {
         const alice_alc_balance = await alc.query.balanceOf(Alice);
         expect(alice_alc_balance.output).to.equal(1000*decimal);
     });
+
+    it("lock the withdraw and the claim for the epochs of the treasury", async () => {
+        const { Alice, als, boardroom, treasury } = await setup();
+        const decimal = 10000000000;
+
+        await als.tx.mint(Alice, 100*decimal);
+        await als.tx.approve(boardroom.address, 100*decimal);
+        await boardroom.tx.transferOperator(treasury.address);
+
+        console.log("the epoch of the treasury is 3s, lock the withdraw for 2 epochs and the claim for 1");
+        await treasury.tx.setPeriod(3000);
+        await treasury.tx.setBoardroomLockup(0, 2, 1);
+        const lockup = await boardroom.query.getLockup();
+        expect(lockup.output.toJSON()).to.deep.equal([2, 1, 3000]);
+
+        console.log("alice stake als, the withdraw is locked");
+        await boardroom.tx.stake(100*decimal);
+        const can_withdraw = await boardroom.query.canWithdraw(Alice);
+        expect(can_withdraw.output).to.equal(false);
+        await expectFail(boardroom.tx.withdraw(100*decimal));
+        const withdraw_unlock = await boardroom.query.withdrawUnlockTime(Alice);
+        const claim_unlock = await boardroom.query.claimUnlockTime(Alice);
+        expect(Number(withdraw_unlock.output.toString()) - Number(claim_unlock.output.toString())).to.equal(3000);
+
+        console.log("a shorter epoch of the treasury shortens the lockup");
+        await treasury.tx.setPeriod(2000);
+        const new_lockup = await boardroom.query.getLockup();
+        expect(new_lockup.output.toJSON()).to.deep.equal([2, 1, 2000]);
+
+        console.log("wait 2 epochs, alice can withdraw");
+        await sleep(5000);
+        const can_withdraw_after = await boardroom.query.canWithdraw(Alice);
+        expect(can_withdraw_after.output).to.equal(true);
+        await boardroom.tx.withdraw(100*decimal);
+        const alice_als_balance = await als.query.balanceOf(Alice);
+        expect(alice_als_balance.output).to.equal(100*decimal);
+    });
+
+    it("stream the reward linearly over the reward duration", async () => {
+        const { Alice, alc, als, boardroom } = await setup();
+        const decimal = 10000000000;
+
+        await als.tx.mint(Alice, 100*decimal);
+        await als.tx.approve(boardroom.address, 100*decimal);
+        await boardroom.tx.stake(100*decimal);
+
+        console.log("allocate 100 ALC streamed over 10s");
+        await alc.tx.mint(Alice, 100*decimal);
+        await alc.tx.approve(boardroom.address, 100*decimal);
+        await boardroom.tx.setRewardDuration(10000);
+        await boardroom.tx.allocateSeigniorage(100*decimal);
+        const rate = await boardroom.query.rewardRate();
+        expect(Number(rate.output.toString())).to.be.greaterThan(0);
+        const finish = await boardroom.query.periodFinish();
+        const update = await boardroom.query.lastUpdateTime();
+        expect(Number(finish.output.toString()) - Number(update.output.toString())).to.equal(10000);
+
+        console.log("the duration can't be changed while streaming");
+        await expectFail(boardroom.tx.setRewardDuration(1));
+
+        console.log("only a part of the reward is earned before the period finish");
+        const earned = await boardroom.query.earned(Alice);
+        expect(Number(earned.output.toString())).to.be.lessThan(100*decimal);
+
+        console.log("the allocation is kept in the snapshot history after the genesis");
+        const count = await boardroom.query.snapshotCount();
+        expect(count.output).to.equal(2);
+        const snapshots = await boardroom.query.getSnapshots(0, 10);
+        expect(snapshots.output.toJSON()[1].rewardReceived).to.equal(100*decimal);
+
+        console.log("wait until the period finish, all the reward is earned");
+        await sleep(11000);
+        const earned_after = await boardroom.query.earned(Alice);
+        expect(Number(earned_after.output.toString())).to.be.closeTo(100*decimal, 100);
+        await boardroom.tx.claimReward();
+        const alice_alc = await alc.query.balanceOf(Alice);
+        expect(Number(alice_alc.output.toString())).to.be.closeTo(100*decimal, 100);
+    });
+
+    it("query the earned reward and the directors", async () => {
+        const { Alice, Bob, alc, als, boardroom } = await setup();
+        const decimal = 10000000000;
+
+        console.log("bob has no seat before the stake");
+        const no_seat = await boardroom.query.getSeat(Bob);
+        expect(no_seat.output.isNone).to.equal(true);
+
+        console.log("alice and bob stake 100 als each");
+        await als.tx.mint(Alice, 100*decimal);
+        await als.tx.approve(boardroom.address, 100*decimal);
+        await boardroom.tx.stake(100*decimal);
+        await als.tx.mint(Bob, 100*decimal);
+        await als.connect(Bob).tx.approve(boardroom.address, 100*decimal);
+        await boardroom.connect(Bob).tx.stake(100*decimal);
+        const count = await boardroom.query.directorCount();
+        expect(count.output).to.equal(2);
+
+        console.log("allocate 100 ALC in 1ms");
+        await alc.tx.mint(Alice, 100*decimal);
+        await alc.tx.approve(boardroom.address, 100*decimal);
+        await boardroom.tx.setRewardDuration(1);
+        await boardroom.tx.allocateSeigniorage(100*decimal);
+
+        console.log("each director earns 50 ALC");
+        const alice_earned = await boardroom.query.earned(Alice);
+        expect(alice_earned.output).to.equal(50*decimal);
+        const bob_earned = await boardroom.query.earned(Bob);
+        expect(bob_earned.output).to.equal(50*decimal);
+        const seat = await boardroom.query.getSeat(Bob);
+        expect(seat.output.isSome).to.equal(true);
+
+        console.log("the directors are paged with their stake and the earned reward");
+        const directors = (await boardroom.query.getDirectors(0, 10)).output.toJSON();
+        expect(directors.length).to.equal(2);
+        for (const director of directors) {
+            expect(director[1]).to.equal(100*decimal);
+            expect(director[3]).to.equal(50*decimal);
+        }
+        const last_page = (await boardroom.query.getDirectors(1, 10)).output.toJSON();
+        expect(last_page.length).to.equal(1);
+        const empty_page = (await boardroom.query.getDirectors(2, 10)).output.toJSON();
+        expect(empty_page.length).to.equal(0);
+
+        console.log("claim the reward, nothing is earned anymore");
+        await boardroom.tx.claimReward();
+        const alice_earned_after = await boardroom.query.earned(Alice);
+        expect(alice_earned_after.output).to.equal(0);
+        const alice_alc = await alc.query.balanceOf(Alice);
+        expect(alice_alc.output).to.equal(50*decimal);
+    });
+
+    it("page the snapshots by index and by time", async () => {
+        const { Alice, alc, als, boardroom } = await setup();
+        const decimal = 10000000000;
+
+        await als.tx.mint(Alice, 100*decimal);
+        await als.tx.approve(boardroom.address, 100*decimal);
+        await boardroom.tx.stake(100*decimal);
+
+        console.log("allocate 10, 20 and 30 ALC");
+        await alc.tx.mint(Alice, 60*decimal);
+        await alc.tx.approve(boardroom.address, 60*decimal);
+        await boardroom.tx.setRewardDuration(1);
+        await boardroom.tx.allocateSeigniorage(10*decimal);
+        await boardroom.tx.allocateSeigniorage(20*decimal);
+        await boardroom.tx.allocateSeigniorage(30*decimal);
+        const count = await boardroom.query.snapshotCount();
+        expect(count.output).to.equal(4);
+
+        console.log("page the snapshots from the first allocation");
+        const page = (await boardroom.query.getSnapshots(1, 2)).output.toJSON();
+        expect(page.length).to.equal(2);
+        expect(page[0].rewardReceived).to.equal(10*decimal);
+        expect(page[1].rewardReceived).to.equal(20*decimal);
+        const all = (await boardroom.query.getSnapshots(0, 1000)).output.toJSON();
+        expect(all.length).to.equal(4);
+
+        console.log("query the snapshots since the second allocation");
+        const from_time = all[2].time;
+        const to_time = all[3].time;
+        const between = (await boardroom.query.getSnapshotsBetween(from_time, to_time)).output.toJSON();
+        expect(between.length).to.equal(2);
+        expect(between[0].rewardReceived).to.equal(20*decimal);
+        expect(between[1].rewardReceived).to.equal(30*decimal);
+        const none = (await boardroom.query.getSnapshotsBetween(to_time, from_time)).output.toJSON();
+        expect(none.length).to.equal(0);
+
+        console.log("the APR of the last 2 allocations");
+        const apr = await boardroom.query.getApr(2, 10000000000, 10000000000);
+        expect(Number(apr.output.toString())).to.be.greaterThan(0);
+        const no_apr = await boardroom.query.getApr(0, 10000000000, 10000000000);
+        expect(no_apr.output).to.equal(0);
+    });
+
+    it("allocate and claim an extra reward token", async () => {
+        const { Alice, Bob, alc, alb, als, boardroom } = await setup();
+        const decimal = 10000000000;
+
+        console.log("add ALB as a reward token distributed by bob");
+        await expectFail(boardroom.tx.addRewardToken(alc.address, Bob));
+        await boardroom.tx.addRewardToken(alb.address, Bob);
+        await expectFail(boardroom.tx.addRewardToken(alb.address, Bob));
+        const tokens = await boardroom.query.rewardTokens();
+        expect(tokens.output.toJSON()).to.deep.equal([alb.address.toString()]);
+
+        await als.tx.mint(Alice, 100*decimal);
+        await als.tx.approve(boardroom.address, 100*decimal);
+        await boardroom.tx.stake(100*decimal);
+        await boardroom.tx.setRewardDuration(1);
+
+        console.log("the operator and bob allocate 100 ALB each");
+        await alb.tx.mint(Alice, 100*decimal);
+        await alb.tx.approve(boardroom.address, 100*decimal);
+        await boardroom.tx.allocateReward(alb.address, 100*decimal);
+        await alb.tx.mint(Bob, 100*decimal);
+        await alb.connect(Bob).tx.approve(boardroom.address, 100*decimal);
+        await boardroom.connect(Bob).tx.allocateReward(alb.address, 100*decimal);
+        const snapshots = (await boardroom.query.getTokenSnapshots(alb.address, 0, 10)).output.toJSON();
+        expect(snapshots.length).to.equal(2);
+
+        console.log("the ALB is earned apart from the ALC");
+        const earned = await boardroom.query.earnedToken(alb.address, Alice);
+        expect(earned.output).to.equal(200*decimal);
+        const alc_earned = await boardroom.query.earned(Alice);
+        expect(alc_earned.output).to.equal(0);
+
+        console.log("claim pays every token earned");
+        await boardroom.tx.claimReward();
+        const alice_alb = await alb.query.balanceOf(Alice);
+        expect(alice_alb.output).to.equal(200*decimal);
+        const earned_after = await boardroom.query.earnedToken(alb.address, Alice);
+        expect(earned_after.output).to.equal(0);
+    });
+
+    it("emergency withdraw forfeits the reward to the next allocation", async () => {
+        const { Alice, Bob, alc, als, boardroom } = await setup();
+        const decimal = 10000000000;
+
+        console.log("lock the withdraw for 2 epochs of 60s");
+        await boardroom.tx.setLockup(2, 1, 60000);
+        await boardroom.tx.setRewardDuration(1);
+
+        console.log("alice and bob stake 100 als each");
+        await als.tx.mint(Alice, 200*decimal);
+        await als.tx.approve(boardroom.address, 200*decimal);
+        await boardroom.tx.stake(100*decimal);
+        await als.tx.mint(Bob, 100*decimal);
+        await als.connect(Bob).tx.approve(boardroom.address, 100*decimal);
+        await boardroom.connect(Bob).tx.stake(100*decimal);
+
+        console.log("allocate 100 ALC, alice stakes again to settle it");
+        await alc.tx.mint(Alice, 200*decimal);
+        await alc.tx.approve(boardroom.address, 200*decimal);
+        await boardroom.tx.allocateSeigniorage(100*decimal);
+        await boardroom.tx.stake(100*decimal);
+        const bob_earned = await boardroom.query.earned(Bob);
+        expect(bob_earned.output).to.equal(50*decimal);
+
+        console.log("bob can't withdraw in the lockup, but the emergency withdraw ignores it");
+        await expectFail(boardroom.connect(Bob).tx.withdraw(100*decimal));
+        await boardroom.connect(Bob).tx.emergencyWithdraw();
+        const bob_als = await als.query.balanceOf(Bob);
+        expect(bob_als.output).to.equal(100*decimal);
+        const bob_alc = await alc.query.balanceOf(Bob);
+        expect(bob_alc.output).to.equal(0);
+        const bob_seat = await boardroom.query.getSeat(Bob);
+        expect(bob_seat.output.isNone).to.equal(true);
+        const forfeited = await boardroom.query.forfeitedReward(alc.address);
+        expect(forfeited.output).to.equal(50*decimal);
+
+        console.log("the forfeited reward is added to the next allocation");
+        await boardroom.tx.allocateSeigniorage(100*decimal);
+        const forfeited_after = await boardroom.query.forfeitedReward(alc.address);
+        expect(forfeited_after.output).to.equal(0);
+        const alice_earned = await boardroom.query.earned(Alice);
+        expect(alice_earned.output).to.equal(200*decimal);
+    });
+
+    it("carry the remainder of the allocation and sweep the rounding dust", async () => {
+        const { Alice, Bob, alc, als, boardroom } = await setup();
+
+        console.log("alice stake 7 units of als");
+        await als.tx.mint(Alice, 7);
+        await als.tx.approve(boardroom.address, 7);
+        await boardroom.tx.stake(7);
+
+        console.log("allocate 100 units of alc over 3ms, 1 unit is carried");
+        await alc.tx.mint(Alice, 102);
+        await alc.tx.approve(boardroom.address, 102);
+        await boardroom.tx.setRewardDuration(3);
+        await boardroom.tx.allocateSeigniorage(100);
+        const carried = await boardroom.query.carriedReward(alc.address);
+        expect(carried.output).to.equal(1);
+
+        console.log("99 units are streamed to the 7 units of als, 1 unit is lost by the rounding");
+        const earned = await boardroom.query.earned(Alice);
+        expect(earned.output).to.equal(98);
+        await boardroom.tx.claimReward();
+        const alice_alc = await alc.query.balanceOf(Alice);
+        expect(alice_alc.output).to.equal(100);
+        const dust = await boardroom.query.rewardDust(alc.address);
+        expect(dust.output).to.equal(1);
+
+        console.log("only the operator sweeps the dust");
+        await expectFail(boardroom.connect(Bob).tx.sweepDust(alc.address, Bob));
+        await boardroom.tx.sweepDust(alc.address, Bob);
+        const bob_alc = await alc.query.balanceOf(Bob);
+        expect(bob_alc.output).to.equal(1);
+        await expectFail(boardroom.tx.sweepDust(alc.address, Bob));
+
+        console.log("the carried unit is added to the next allocation");
+        await boardroom.tx.allocateSeigniorage(2);
+        const carried_after = await boardroom.query.carriedReward(alc.address);
+        expect(carried_after.output).to.equal(0);
+    });
 });
@@ -75,9 +75,184 @@ describe("treasury", () => {
             salt: tsForTreasury
         });
 
-        return { Alice, Bob, alc, alb, als, oracle, boardroom, treasury };
+        return { Alice, Bob, alc, alb, als, oracle, util, boardroom, treasury };
     }
 
+    async function deploy(Alice, contract, ...args) {
+        const factory = await getContractFactory(contract, Alice);
+        const salt = (new Date().getTime()).toString();
+        return await factory.deploy('new', ...args, {
+            gasLimit: "400000000000",
+            value: "1000000000000",
+            salt
+        });
+    }
+
+    async function handOver(alc, alb, als, boardroom, treasury) {
+        await alc.tx.transferOperator(treasury.address);
+        await alb.tx.transferOperator(treasury.address);
+        await als.tx.transferOperator(treasury.address);
+        await boardroom.tx.transferOperator(treasury.address);
+    }
+
+    function sleep(ms) {
+        return new Promise(resolve => setTimeout(resolve, ms));
+    }
+
+    async function expectFail(tx) {
+        let failed = false;
+        try {
+            await tx;
+        } catch (e) {
+            failed = true;
+        }
+        expect(failed).to.equal(true);
+    }
+
+    it("pay the keeper only when the allocation succeeds", async () => {
+        const { Alice, Bob, alc, alb, als, oracle, boardroom, treasury } = await setup();
+
+        const decimal = 10000000000;
+
+        await alc.tx.mint(Alice, 1000*decimal);
+        await handOver(alc, alb, als, boardroom, treasury);
+
+        console.log("keeper reward is 1 alc");
+        await treasury.tx.setKeeperReward(1*decimal, 0);
+        const treasury_bob = treasury.connect(Bob);
+
+        console.log("cash price 1.0 is not above the ceiling, allocation fails");
+        await oracle.tx.updateCashPrice(10000000000, 123);
+        await expectFail(treasury_bob.tx.allocateSeigniorage(0));
+        const bob_failed_balance = await alc.query.balanceOf(Bob);
+        expect(bob_failed_balance.output).to.equal(0);
+        const failed_paid = await treasury.query.keeperRewardsPaid(0);
+        expect(failed_paid.output).to.equal(0);
+
+        console.log("cash price 1.1, allocation succeeds");
+        await oracle.tx.updateCashPrice(11000000000, 234);
+        await treasury_bob.tx.allocateSeigniorage(0);
+        const bob_balance = await alc.query.balanceOf(Bob);
+        expect(bob_balance.output).to.equal(1*decimal);
+        const paid = await treasury.query.keeperRewardsPaid(0);
+        expect(paid.output).to.equal(1*decimal);
+
+        console.log("the same epoch can't be allocated twice");
+        await expectFail(treasury_bob.tx.allocateSeigniorage(0));
+        const bob_final_balance = await alc.query.balanceOf(Bob);
+        expect(bob_final_balance.output).to.equal(1*decimal);
+    });
+
+    it("allocate twice while the boardroom is empty", async () => {
+        const { Alice, alc, alb, als, oracle, boardroom, treasury } = await setup();
+
+        const decimal = 10000000000;
+
+        await alc.tx.mint(Alice, 1000*decimal);
+        await handOver(alc, alb, als, boardroom, treasury);
+
+        console.log("a new epoch every 1ms");
+        await treasury.tx.setPeriod(1);
+        await oracle.tx.updateCashPrice(11000000000, 123);
+
+        console.log("allocate with no ALS staked, no ALB and no DAO fund");
+        await treasury.tx.allocateSeigniorage(0);
+        const first_supply = await alc.query.totalSupply();
+        expect(first_supply.output).to.equal(1000*decimal);
+
+        console.log("allocate again in the next epoch");
+        await treasury.tx.allocateSeigniorage(0);
+        const second_supply = await alc.query.totalSupply();
+        expect(second_supply.output).to.equal(1000*decimal);
+    });
+
+    it("keep the accounting of two pools separate", async () => {
+        const { Alice, Bob, alc, alb, als, oracle, util, boardroom, treasury } = await setup();
+
+        const decimal = 10000000000;
+
+        console.log("deploy the second cash set");
+        const alc2 = await deploy(Alice, 'asset', '0', 'ALC2', 'ALC2', '10');
+        const alb2 = await deploy(Alice, 'asset', '0', 'ALB2', 'ALB2', '10');
+        const oracle2 = await deploy(Alice, 'oracle');
+        const boardroom2 = await deploy(Alice, 'boardroom', alc2.address, als.address, util.address);
+
+        await alc.tx.mint(Alice, 1000*decimal);
+        await alc2.tx.mint(Alice, 2000*decimal);
+        await handOver(alc, alb, als, boardroom, treasury);
+        await alc2.tx.transferOperator(treasury.address);
+        await alb2.tx.transferOperator(treasury.address);
+        await boardroom2.tx.transferOperator(treasury.address);
+
+        console.log("add the pool 1");
+        await treasury.tx.addPool(alc2.address, alb2.address, oracle2.address, boardroom2.address);
+        const pool_count = await treasury.query.poolCount();
+        expect(pool_count.output).to.equal(2);
+
+        console.log("keeper reward is 1% of the seigniorage");
+        await treasury.tx.setKeeperReward(0, 100);
+        await oracle.tx.updateCashPrice(11000000000, 123);
+        await oracle2.tx.updateCashPrice(11000000000, 123);
+
+        console.log("bob allocates both pools");
+        const treasury_bob = treasury.connect(Bob);
+        await treasury_bob.tx.allocateSeigniorage(0);
+        await treasury_bob.tx.allocateSeigniorage(1);
+
+        const paid0 = await treasury.query.keeperRewardsPaid(0);
+        expect(paid0.output).to.equal(1*decimal);
+        const paid1 = await treasury.query.keeperRewardsPaid(1);
+        expect(paid1.output).to.equal(2*decimal);
+        const bob_alc = await alc.query.balanceOf(Bob);
+        expect(bob_alc.output).to.equal(1*decimal);
+        const bob_alc2 = await alc2.query.balanceOf(Bob);
+        expect(bob_alc2.output).to.equal(2*decimal);
+
+        console.log("the share sale of the pool 1 doesn't open the pool 0's");
+        await treasury.tx.setShareSaleTrigger(1, true, 9000);
+        const sale0 = await treasury.query.getShareSaleTrigger(0);
+        expect(sale0.output.toJSON().enabled).to.equal(false);
+        const sale1 = await treasury.query.getShareSaleTrigger(1);
+        expect(sale1.output.toJSON().enabled).to.equal(true);
+    });
+
+    it("bid, settle and claim the auction bonds", async () => {
+        const { Alice, alc, alb, als, oracle, boardroom, treasury } = await setup();
+
+        const decimal = 10000000000;
+
+        await alc.tx.mint(Alice, 1000*decimal);
+        await alc.tx.approve(treasury.address, 1000*decimal);
+        await handOver(alc, alb, als, boardroom, treasury);
+
+        console.log("the auction price is fixed at 1 ALC per ALB");
+        await treasury.tx.setAuction(0, true, 10000, 10000, 60 * 60 * 1000);
+        await oracle.tx.updateCashPrice(9000000000, 123);
+
+        console.log("alice bids more than the capacity, the round is filled");
+        await treasury.tx.bidBonds(0, 200*decimal, 1*decimal);
+        const epoch = (await treasury.query.epoch()).output;
+        const round = await treasury.query.getAuctionRound(0, epoch);
+        expect(round.output.toJSON().capacity).to.equal(100*decimal);
+        const bid = await treasury.query.getAuctionBid(0, epoch, Alice);
+        expect(bid.output).to.equal(100*decimal);
+        const alc_balance = await alc.query.balanceOf(Alice);
+        expect(alc_balance.output).to.equal(900*decimal);
+        const clearing_price = await treasury.query.getClearingPrice(0, epoch);
+        expect(clearing_price.output).to.equal(1*decimal);
+
+        console.log("the claim is paused with the bond purchase");
+        await treasury.tx.pause('BuyBonds');
+        await expectFail(treasury.tx.claimAuctionBonds(0, epoch));
+        await treasury.tx.unpause('BuyBonds');
+
+        console.log("alice claims the bonds at the clearing price");
+        await treasury.tx.claimAuctionBonds(0, epoch);
+        const alb_balance = await alb.query.balanceOf(Alice);
+        expect(alb_balance.output).to.equal(100*decimal);
+        await expectFail(treasury.tx.claimAuctionBonds(0, epoch));
+    });
+
     it("buy and redeem bonds", async () => {
         const { Alice, alc, alb, als, oracle, boardroom, treasury } = await setup();
 
@@ -120,15 +295,18 @@ describe("treasury", () => {
         expect(b_price.output).to.equal(9000000000);
 
         console.log("alice buy bonds");
-        await treasury.tx.buyBonds(100*decimal, 1*decimal);
+        const deadline = new Date().getTime() + 60 * 60 * 1000;
+        // the bonds are bought with the cash's price 0.9.
+        const bond_out = Math.floor(100*decimal * decimal / 9000000000);
+        await treasury.tx.buyBonds(0, 100*decimal, 1*decimal, bond_out, deadline);
         const alc_balance = await alc.query.balanceOf(Alice);
         expect(alc_balance.output).to.equal(0);
 
         const alb_balance = await alb.query.balanceOf(Alice);
-        expect(alb_balance.output).to.equal(1111111111111);
+        expect(alb_balance.output).to.equal(bond_out);
 
         console.log("alb approve to treasury");
-        await alb.tx.approve(treasury.address, 1111111111111);
+        await alb.tx.approve(treasury.address, bond_out);
 
         console.log("update cash price to 1.1");
         await oracle.tx.updateCashPrice(11000000000, 234);
@@ -136,8 +314,206 @@ describe("treasury", () => {
         expect(l_price.output).to.equal(11000000000);
 
         console.log("alice redeem bonds");
-        await treasury.tx.redeemBonds(1111111111111);
+        await treasury.tx.redeemBonds(0, bond_out, bond_out, deadline);
         const final_balance = await alc.query.balanceOf(Alice);
-        expect(final_balance.output).to.equal(1111111111111);
+        expect(final_balance.output).to.equal(bond_out);
+    });
+
+    it("import the seigniorage of the old treasury once", async () => {
+        const { alc, treasury } = await setup();
+
+        const decimal = 10000000000;
+
+        console.log("the old treasury transfers 100 ALC of reserve");
+        await alc.tx.mint(treasury.address, 100*decimal);
+
+        console.log("the seigniorage can't be more than the reserve");
+        await expectFail(treasury.tx.importSeigniorage(0, 101*decimal));
+
+        await treasury.tx.importSeigniorage(0, 100*decimal);
+        const accumulated = await treasury.query.accumulatedSeigniorage(0);
+        expect(accumulated.output).to.equal(100*decimal);
+
+        console.log("the pool is imported only once");
+        await expectFail(treasury.tx.importSeigniorage(0, 0));
+    });
+
+    it("round the fractional mint up and the redeem down", async () => {
+        const { Alice, alc, alb, als, oracle, boardroom, treasury } = await setup();
+
+        const decimal = 10000000000;
+
+        const ausd = await deploy(Alice, 'asset', '0', 'aUSD', 'aUSD', '10');
+        await treasury.tx.setAUsd(ausd.address);
+        await treasury.tx.setCollateral(0, true, 5000, 100);
+        await handOver(alc, alb, als, boardroom, treasury);
+
+        console.log("ALS is 1.0");
+        await oracle.tx.updateSharePrice(10000000000, 123);
+
+        await ausd.tx.mint(Alice, 10);
+        await ausd.tx.approve(treasury.address, 10);
+        await als.tx.mint(Alice, 10);
+        await als.tx.approve(treasury.address, 10);
+
+        console.log("1 unit of ALC costs 1 unit of aUSD and 1 unit of ALS, not nothing");
+        await expectFail(treasury.tx.mintFractional(0, 1, 0, 0));
+        await treasury.tx.mintFractional(0, 1, 1, 1);
+        const alc_balance = await alc.query.balanceOf(Alice);
+        expect(alc_balance.output).to.equal(1);
+        const ausd_balance = await ausd.query.balanceOf(Alice);
+        expect(ausd_balance.output).to.equal(9);
+        const als_balance = await als.query.balanceOf(Alice);
+        expect(als_balance.output).to.equal(9);
+
+        console.log("3 units of ALC cost 2 units of aUSD and 2 units of ALS");
+        await treasury.tx.mintFractional(0, 3, 2, 2);
+        const ausd_after = await ausd.query.balanceOf(Alice);
+        expect(ausd_after.output).to.equal(7);
+        const als_after = await als.query.balanceOf(Alice);
+        expect(als_after.output).to.equal(7);
+
+        console.log("redeeming 3 units of ALC pays 1 unit of aUSD and 2 units of ALS");
+        await alc.tx.approve(treasury.address, 3);
+        await treasury.tx.redeemFractional(0, 3, 1, 2);
+        const ausd_redeemed = await ausd.query.balanceOf(Alice);
+        expect(ausd_redeemed.output).to.equal(8);
+        const als_redeemed = await als.query.balanceOf(Alice);
+        expect(als_redeemed.output).to.equal(9);
+    });
+
+    it("share the cap of the share sale across the pools", async () => {
+        const { Alice, alc, alb, als, oracle, util, boardroom, treasury } = await setup();
+
+        const decimal = 10000000000;
+
+        console.log("deploy the second cash set");
+        const alc2 = await deploy(Alice, 'asset', '0', 'ALC2', 'ALC2', '10');
+        const alb2 = await deploy(Alice, 'asset', '0', 'ALB2', 'ALB2', '10');
+        const oracle2 = await deploy(Alice, 'oracle');
+        const boardroom2 = await deploy(Alice, 'boardroom', alc2.address, als.address, util.address);
+
+        await alc.tx.mint(Alice, 100*decimal);
+        await alc2.tx.mint(Alice, 100*decimal);
+        await als.tx.mint(Alice, 10000*decimal);
+        await alc.tx.approve(treasury.address, 100*decimal);
+        await alc2.tx.approve(treasury.address, 100*decimal);
+        await handOver(alc, alb, als, boardroom, treasury);
+        await alc2.tx.transferOperator(treasury.address);
+        await alb2.tx.transferOperator(treasury.address);
+        await boardroom2.tx.transferOperator(treasury.address);
+        await treasury.tx.addPool(alc2.address, alb2.address, oracle2.address, boardroom2.address);
+
+        console.log("both pools sell ALS below 0.9, 100 ALS per epoch in total");
+        await treasury.tx.setShareSale(100*decimal, 1000);
+        await treasury.tx.setShareSaleTrigger(0, true, 9000);
+        await treasury.tx.setShareSaleTrigger(1, true, 9000);
+        await oracle.tx.updateCashPrice(8000000000, 123);
+        await oracle.tx.updateSharePrice(10000000000, 125);
+        await oracle2.tx.updateCashPrice(8000000000, 123);
+        await oracle2.tx.updateSharePrice(10000000000, 125);
+
+        console.log("sell 40 ALS for 50 ALC in the pool 0");
+        await treasury.tx.sellShare(0, 50*decimal, 40*decimal);
+        const sale = await treasury.query.getShareSale();
+        expect(sale.output.toJSON().sold).to.equal(40*decimal);
+
+        console.log("the pool 1 can't sell another 80 ALS in the epoch");
+        await expectFail(treasury.tx.sellShare(1, 100*decimal, 0));
+
+        console.log("but it can sell the 40 ALS left");
+        await treasury.tx.sellShare(1, 50*decimal, 40*decimal);
+        const sale_after = await treasury.query.getShareSale();
+        expect(sale_after.output.toJSON().sold).to.equal(80*decimal);
+        const als_balance = await als.query.balanceOf(Alice);
+        expect(als_balance.output).to.equal(10080*decimal);
+    });
+
+    it("bid the auction bonds when the price falls below the limit", async () => {
+        const { Alice, alc, alb, als, oracle, boardroom, treasury } = await setup();
+
+        const decimal = 10000000000;
+
+        await alc.tx.mint(Alice, 1000*decimal);
+        await alc.tx.approve(treasury.address, 1000*decimal);
+        await handOver(alc, alb, als, boardroom, treasury);
+
+        console.log("the auction price descends from 1.0 to 0.6 in 40s");
+        await treasury.tx.setAuction(0, true, 10000, 6000, 40 * 1000);
+        await oracle.tx.updateCashPrice(9000000000, 123);
+
+        console.log("alice opens the round below her limit of 1.0");
+        await treasury.tx.bidBonds(0, 10*decimal, 1*decimal);
+
+        console.log("the price is still above the limit of 0.9");
+        await expectFail(treasury.tx.bidBonds(0, 10*decimal, 9000000000));
+
+        console.log("wait until the price falls below 0.9");
+        await sleep(12000);
+        await treasury.tx.bidBonds(0, 10*decimal, 9000000000);
+        const epoch = (await treasury.query.epoch()).output;
+        const bid = await treasury.query.getAuctionBid(0, epoch, Alice);
+        expect(bid.output).to.equal(20*decimal);
+        const price = await treasury.query.getAuctionPrice(0);
+        expect(Number(price.output.toString()) < 9000000000).to.equal(true);
+    });
+
+    it("sell the ALS below the trigger price within the max inflation", async () => {
+        const { Alice, alc, alb, als, oracle, boardroom, treasury } = await setup();
+
+        const decimal = 10000000000;
+
+        await alc.tx.mint(Alice, 100*decimal);
+        await alc.tx.approve(treasury.address, 100*decimal);
+        await als.tx.mint(Alice, 1000*decimal);
+        await handOver(alc, alb, als, boardroom, treasury);
+
+        console.log("the sale is closed by default");
+        await oracle.tx.updateCashPrice(8000000000, 123);
+        await oracle.tx.updateSharePrice(10000000000, 125);
+        await expectFail(treasury.tx.sellShare(0, 10*decimal, 0));
+
+        console.log("open the sale below 0.9, at most 5% of the ALS supply per epoch");
+        await treasury.tx.setShareSale(100*decimal, 500);
+        await treasury.tx.setShareSaleTrigger(0, true, 9000);
+        await expectFail(treasury.tx.setShareSale(100*decimal, 1001));
+
+        console.log("the price 0.95 is above the trigger");
+        await oracle.tx.updateCashPrice(9500000000, 127);
+        await expectFail(treasury.tx.sellShare(0, 10*decimal, 0));
+
+        console.log("sell 40 ALS for 50 ALC at the price 0.8, the ALC is burnt");
+        await oracle.tx.updateCashPrice(8000000000, 129);
+        await treasury.tx.sellShare(0, 50*decimal, 40*decimal);
+        const alc_balance = await alc.query.balanceOf(Alice);
+        expect(alc_balance.output).to.equal(50*decimal);
+        const alc_supply = await alc.query.totalSupply();
+        expect(alc_supply.output).to.equal(50*decimal);
+        const als_balance = await als.query.balanceOf(Alice);
+        expect(als_balance.output).to.equal(1040*decimal);
+
+        console.log("another 16 ALS exceeds the 50 ALS inflation of the epoch");
+        await expectFail(treasury.tx.sellShare(0, 20*decimal, 0));
+        await treasury.tx.sellShare(0, 10*decimal, 8*decimal);
+        const sale = await treasury.query.getShareSale();
+        expect(sale.output.toJSON().totalSold).to.equal(48*decimal);
+
+        console.log("the sale is paused by the guardian");
+        await treasury.tx.pause('SellShare');
+        await expectFail(treasury.tx.sellShare(0, 1*decimal, 0));
+    });
+
+    it("keep the allowlist phase shorter than the epoch", async () => {
+        const { treasury } = await setup();
+
+        console.log("the allowlist phase is 3s of each epoch");
+        await expectFail(treasury.tx.setBondLimit(0, 0, 24 * 60 * 60 * 1000));
+        await treasury.tx.setBondLimit(0, 0, 3000);
+
+        console.log("the epoch can't be shortened below the allowlist phase");
+        await expectFail(treasury.tx.setPeriod(3000));
+        await treasury.tx.setPeriod(4000);
+        const limit = await treasury.query.getBondLimit(0);
+        expect(limit.output.toJSON().allowlistDuration).to.equal(3000);
     });
 });
@@ -0,0 +1,200 @@
+import { expect } from "chai";
+import { artifacts, network, patract } from "redspot";
+
+const { getContractFactory, getRandomSigner } = patract;
+
+const { api, getAddresses, getSigners } = network;
+
+describe("vault", () => {
+    after(() => {
+        return api.disconnect();
+    });
+
+    async function deploy(Alice, contract, ...args) {
+        const factory = await getContractFactory(contract, Alice);
+        const salt = (new Date().getTime()).toString();
+        return await factory.deploy('new', ...args, {
+            gasLimit: "400000000000",
+            value: "1000000000000",
+            salt
+        });
+    }
+
+    async function expectFail(tx) {
+        let failed = false;
+        try {
+            await tx;
+        } catch (e) {
+            failed = true;
+        }
+        expect(failed).to.equal(true);
+    }
+
+    function sleep(ms) {
+        return new Promise(resolve => setTimeout(resolve, ms));
+    }
+
+    async function setup() {
+        await api.isReady
+
+        const signerAddresses = await getAddresses();
+        const Alice = signerAddresses[0];
+        const Bob = "5FHneW46xGXgs5mUiveU4sbTyGBzmstUspZC92UhjJM694ty"; // Bob Address
+
+        const alc = await deploy(Alice, 'asset', '0', 'ALC', 'ALC', '10');
+        const als = await deploy(Alice, 'asset', '0', 'ALS', 'ALS', '10');
+        const vshare = await deploy(Alice, 'asset', '0', 'vALS', 'vALS', '10');
+        const oracle = await deploy(Alice, 'oracle');
+        const util = await deploy(Alice, 'util', '10');
+        const boardroom = await deploy(Alice, 'boardroom', alc.address, als.address, util.address);
+        // no pair, the ALC reward is kept and valued by the oracle.
+        const vault = await deploy(Alice, 'vault', alc.address, als.address, boardroom.address, vshare.address, oracle.address, null);
+
+        console.log("ALC and ALS are both 1.0");
+        await oracle.tx.updateCashPrice(10000000000, 123);
+        await oracle.tx.updateSharePrice(10000000000, 123);
+
+        console.log("transfer operator of the vault share to the vault");
+        await vshare.tx.transferOperator(vault.address);
+
+        return { Alice, Bob, alc, als, vshare, oracle, boardroom, vault };
+    }
+
+    it("mint the shares by the assets", async () => {
+        const { Alice, als, vshare, vault } = await setup();
+        const decimal = 10000000000;
+
+        await als.tx.mint(Alice, 200*decimal);
+        await als.tx.approve(vault.address, 200*decimal);
+
+        console.log("the first deposit mints the dead shares to the vault");
+        await vault.tx.deposit(100*decimal);
+        const alice_shares = await vshare.query.balanceOf(Alice);
+        expect(alice_shares.output).to.equal(100*decimal - 1000);
+        const dead_shares = await vshare.query.balanceOf(vault.address);
+        expect(dead_shares.output).to.equal(1000);
+        const supply = await vshare.query.totalSupply();
+        expect(supply.output).to.equal(100*decimal);
+
+        console.log("a donation raises the price of a share");
+        await als.tx.mint(vault.address, 100*decimal);
+        const total = await vault.query.totalAssets();
+        expect(total.output).to.equal(200*decimal);
+        const shares = await vault.query.convertToShares(100*decimal);
+        expect(shares.output).to.equal(50*decimal);
+
+        console.log("the second deposit gets the shares by the price");
+        await vault.tx.deposit(100*decimal);
+        const alice_shares_after = await vshare.query.balanceOf(Alice);
+        expect(alice_shares_after.output).to.equal(150*decimal - 1000);
+        const assets = await vault.query.convertToAssets(50*decimal);
+        expect(assets.output).to.equal(100*decimal);
+    });
+
+    it("count and harvest the reward", async () => {
+        const { Alice, alc, als, boardroom, vault } = await setup();
+        const decimal = 10000000000;
+
+        await als.tx.mint(Alice, 100*decimal);
+        await als.tx.approve(vault.address, 100*decimal);
+        await vault.tx.deposit(100*decimal);
+
+        console.log("harvest stakes the ALS above the 10% buffer");
+        await vault.tx.harvest(0);
+        const staked = await vault.query.staked();
+        expect(staked.output).to.equal(90*decimal);
+        const idle = await vault.query.idle();
+        expect(idle.output).to.equal(10*decimal);
+
+        console.log("allocate 90 ALC to the boardroom in 1ms");
+        await alc.tx.mint(Alice, 90*decimal);
+        await alc.tx.approve(boardroom.address, 90*decimal);
+        await boardroom.tx.setRewardDuration(1);
+        await boardroom.tx.allocateSeigniorage(90*decimal);
+
+        console.log("the pending reward is counted in the assets");
+        const reward = await vault.query.reward();
+        expect(reward.output).to.equal(90*decimal);
+        const total = await vault.query.totalAssets();
+        expect(total.output).to.equal(190*decimal);
+
+        console.log("harvest claims the reward");
+        await vault.tx.harvest(0);
+        const vault_alc = await alc.query.balanceOf(vault.address);
+        expect(vault_alc.output).to.equal(90*decimal);
+        const total_after = await vault.query.totalAssets();
+        expect(total_after.output).to.equal(190*decimal);
+    });
+
+    it("withdraw from the buffer, and from the boardroom after the lockup", async () => {
+        const { Alice, als, vshare, boardroom, vault } = await setup();
+        const decimal = 10000000000;
+
+        console.log("lock the withdraw for 2 epochs of 3s");
+        await boardroom.tx.setLockup(2, 1, 3000);
+
+        await als.tx.mint(Alice, 100*decimal);
+        await als.tx.approve(vault.address, 100*decimal);
+        await vault.tx.deposit(100*decimal);
+        await vault.tx.harvest(0);
+        await vshare.tx.approve(vault.address, 100*decimal);
+
+        console.log("the buffer pays the small withdrawal");
+        await vault.tx.withdraw(5*decimal);
+        const alice_als = await als.query.balanceOf(Alice);
+        expect(alice_als.output).to.equal(5*decimal);
+
+        console.log("the staked ALS is in the lockup");
+        await expectFail(vault.tx.withdraw(50*decimal));
+
+        console.log("wait until the lockup ends");
+        await sleep(7000);
+        await vault.tx.withdraw(50*decimal);
+        const alice_als_after = await als.query.balanceOf(Alice);
+        expect(alice_als_after.output).to.equal(55*decimal);
+
+        console.log("the buffer is refilled");
+        const idle = await vault.query.idle();
+        expect(idle.output).to.equal(45*decimal/10);
+        const staked = await vault.query.staked();
+        expect(staked.output).to.equal(405*decimal/10);
+    });
+
+    it("withdraw only the ALS, the reward not harvested is left", async () => {
+        const { Alice, Bob, alc, als, vshare, boardroom, vault } = await setup();
+        const decimal = 10000000000;
+
+        await als.tx.mint(Alice, 100*decimal);
+        await als.tx.approve(vault.address, 100*decimal);
+        await vault.tx.deposit(100*decimal);
+
+        console.log("only the operator harvests");
+        await expectFail(vault.connect(Bob).tx.harvest(0));
+        await vault.tx.harvest(0);
+
+        console.log("allocate 90 ALC, harvest keeps it without a pair");
+        await alc.tx.mint(Alice, 90*decimal);
+        await alc.tx.approve(boardroom.address, 90*decimal);
+        await boardroom.tx.setRewardDuration(1);
+        await boardroom.tx.allocateSeigniorage(90*decimal);
+        await vault.tx.harvest(0);
+        const total = await vault.query.totalAssets();
+        expect(total.output).to.equal(190*decimal);
+
+        console.log("alice withdraws all her shares, she gets the staked and idle ALS");
+        const shares = 100*decimal - 1000;
+        const preview = await vault.query.previewWithdraw(shares);
+        expect(preview.output).to.equal(shares);
+        await vshare.tx.approve(vault.address, shares);
+        await vault.tx.withdraw(shares);
+        const alice_als = await als.query.balanceOf(Alice);
+        expect(alice_als.output).to.equal(shares);
+
+        console.log("the ALC is left to the dead shares");
+        const vault_alc = await alc.query.balanceOf(vault.address);
+        expect(vault_alc.output).to.equal(90*decimal);
+        const staked = await vault.query.staked();
+        const idle = await vault.query.idle();
+        expect(Number(staked.output.toString()) + Number(idle.output.toString())).to.equal(1000);
+    });
+});